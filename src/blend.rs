@@ -0,0 +1,151 @@
+//! Premultiplied alpha blending of [`RgbaNoPadding<8>`](`crate::pixel_formats::RgbaNoPadding`) pixels.
+//!
+//! The `*_pixels` and `*_color` kernels blend whole segments at once, vectorized where the CPU supports it.
+//! They produce exactly the same bytes as the per-pixel functions, which also serve as their portable fallback.
+
+use std::convert::TryInto;
+use tap::{Conv, TryConv};
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(miri)))]
+mod x86;
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(miri)))]
+use x86 as simd;
+
+/// No vector kernels, so that everything is left to the scalar loops.
+#[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), not(miri))))]
+mod simd {
+	pub(super) fn under_rgba8(_dest: &mut [u8], _src: &[u8]) -> usize {
+		0
+	}
+
+	pub(super) fn over_rgba8(_dest: &mut [u8], _src: &[u8]) -> usize {
+		0
+	}
+}
+
+/// Blends `src` under `dest`, as [`Sprite`](`crate::Sprite`)s are drawn.
+pub(crate) fn under_rgba8(dest: &mut [u8], src: [u8; 4]) {
+	let dest_alpha = dest[3];
+
+	for (src, dest) in src.iter().zip(dest) {
+		*dest = (*dest).saturating_add(
+			((*src).conv::<u16>() * (u8::MAX - dest_alpha).conv::<u16>() / u8::MAX.conv::<u16>())
+				.try_conv::<u8>()
+				.expect("infallible"),
+		);
+	}
+}
+
+/// Blends `src` over `dest`, as [`Effect`](`crate::Effect`)s are drawn.
+pub(crate) fn over_rgba8(dest: &mut [u8], src: [u8; 4]) {
+	let src_alpha = src[3];
+
+	for (src, dest) in src.iter().zip(dest) {
+		*dest = src.saturating_add(
+			((*dest).conv::<u16>() * (u8::MAX - src_alpha).conv::<u16>() / u8::MAX.conv::<u16>())
+				.try_conv::<u8>()
+				.expect("infallible"),
+		);
+	}
+}
+
+/// Scales all channels of `color` by `coverage`, with `u8::MAX` meaning full coverage.
+pub(crate) fn scale_rgba8(color: [u8; 4], coverage: u8) -> [u8; 4] {
+	let mut scaled = [0; 4];
+	for (scaled, channel) in scaled.iter_mut().zip(&color) {
+		*scaled = ((*channel).conv::<u16>() * coverage.conv::<u16>() / u8::MAX.conv::<u16>())
+			.try_conv::<u8>()
+			.expect("infallible");
+	}
+	scaled
+}
+
+/// Blends the pixels in `src` under those in `dest`, as [`Sprite`](`crate::Sprite`)s are drawn.
+///
+/// # Panics
+///
+/// Iff `dest` and `src` differ in length.
+pub(crate) fn under_rgba8_pixels(dest: &mut [u8], src: &[u8]) {
+	assert_eq!(dest.len(), src.len());
+
+	let done = simd::under_rgba8(dest, src);
+	for (dest, src) in dest[done..]
+		.chunks_exact_mut(4)
+		.zip(src[done..].chunks_exact(4))
+	{
+		under_rgba8(dest, src.try_into().expect("infallible"));
+	}
+}
+
+/// Blends the pixels in `src` over those in `dest`, as [`Effect`](`crate::Effect`)s are drawn.
+///
+/// # Panics
+///
+/// Iff `dest` and `src` differ in length.
+pub(crate) fn over_rgba8_pixels(dest: &mut [u8], src: &[u8]) {
+	assert_eq!(dest.len(), src.len());
+
+	let done = simd::over_rgba8(dest, src);
+	for (dest, src) in dest[done..]
+		.chunks_exact_mut(4)
+		.zip(src[done..].chunks_exact(4))
+	{
+		over_rgba8(dest, src.try_into().expect("infallible"));
+	}
+}
+
+/// Blends `color` under each pixel in `dest`.
+pub(crate) fn under_rgba8_color(dest: &mut [u8], color: [u8; 4]) {
+	with_color(dest, color, under_rgba8_pixels)
+}
+
+/// Blends `color` over each pixel in `dest`.
+pub(crate) fn over_rgba8_color(dest: &mut [u8], color: [u8; 4]) {
+	with_color(dest, color, over_rgba8_pixels)
+}
+
+/// Blends the pixels from `pixels` under those in `dest`, without collecting them first.
+///
+/// # Panics
+///
+/// Iff `pixels` yields fewer pixels than `dest` holds.
+pub(crate) fn under_rgba8_iter(dest: &mut [u8], pixels: impl IntoIterator<Item = [u8; 4]>) {
+	with_pixels(dest, pixels, under_rgba8_pixels)
+}
+
+/// Blends the pixels from `pixels` over those in `dest`, without collecting them first.
+///
+/// # Panics
+///
+/// Iff `pixels` yields fewer pixels than `dest` holds.
+pub(crate) fn over_rgba8_iter(dest: &mut [u8], pixels: impl IntoIterator<Item = [u8; 4]>) {
+	with_pixels(dest, pixels, over_rgba8_pixels)
+}
+
+/// Runs `blend` on chunks of `dest` against matching runs of `pixels`, buffered on the stack.
+fn with_pixels(
+	dest: &mut [u8],
+	pixels: impl IntoIterator<Item = [u8; 4]>,
+	blend: fn(&mut [u8], &[u8]),
+) {
+	let mut pixels = pixels.into_iter();
+	let mut src = [0; 256];
+	for dest in dest.chunks_mut(src.len()) {
+		let src = &mut src[..dest.len()];
+		for src in src.chunks_exact_mut(4) {
+			src.copy_from_slice(&pixels.next().expect("too few pixels"));
+		}
+		blend(dest, src);
+	}
+}
+
+/// Runs `blend` on chunks of `dest` against matching runs of `color`.
+fn with_color(dest: &mut [u8], color: [u8; 4], blend: fn(&mut [u8], &[u8])) {
+	let mut src = [0; 256];
+	for pixel in src.chunks_exact_mut(4) {
+		pixel.copy_from_slice(&color);
+	}
+	for dest in dest.chunks_mut(src.len()) {
+		blend(dest, &src[..dest.len()]);
+	}
+}
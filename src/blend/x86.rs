@@ -0,0 +1,185 @@
+//! SSE2 and AVX2 kernels, selected at runtime.
+//!
+//! Both compute `base + scaled * (255 - base alpha) / 255` per channel, with flooring division and saturating addition,
+//! like the scalar functions. The division is exact as a multiplication by `0x8081` followed by a right shift by 23,
+//! for all products of two bytes.
+
+#[cfg(target_arch = "x86")]
+#[allow(clippy::wildcard_imports)]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+#[allow(clippy::wildcard_imports)]
+use std::arch::x86_64::*;
+
+/// Blends a prefix of `src` under `dest`, returning its length in bytes.
+pub(super) fn under_rgba8(dest: &mut [u8], src: &[u8]) -> usize {
+	blend(dest, src, true)
+}
+
+/// Blends a prefix of `src` over `dest`, returning its length in bytes.
+pub(super) fn over_rgba8(dest: &mut [u8], src: &[u8]) -> usize {
+	blend(dest, src, false)
+}
+
+fn blend(dest: &mut [u8], src: &[u8], under: bool) -> usize {
+	assert_eq!(dest.len(), src.len());
+
+	if is_x86_feature_detected!("avx2") {
+		// SAFETY: AVX2 is available.
+		unsafe { avx2(dest, src, under) }
+	} else if is_x86_feature_detected!("sse2") {
+		// SAFETY: SSE2 is available.
+		unsafe { sse2(dest, src, under) }
+	} else {
+		0
+	}
+}
+
+#[target_feature(enable = "avx2")]
+#[allow(clippy::cast_ptr_alignment, clippy::cast_possible_wrap)]
+unsafe fn avx2(dest: &mut [u8], src: &[u8], under: bool) -> usize {
+	let len = dest.len() / 32 * 32;
+	let zero = _mm256_setzero_si256();
+	let ones = _mm256_set1_epi8(-1);
+	let reciprocal = _mm256_set1_epi16(0x8081_u16 as i16);
+	for i in (0..len).step_by(32) {
+		let dest_pixels = _mm256_loadu_si256(dest.as_ptr().add(i).cast());
+		let src_pixels = _mm256_loadu_si256(src.as_ptr().add(i).cast());
+		let (base, scaled) = if under {
+			(dest_pixels, src_pixels)
+		} else {
+			(src_pixels, dest_pixels)
+		};
+
+		let alpha = _mm256_srli_epi32(base, 24);
+		let alpha = _mm256_or_si256(alpha, _mm256_slli_epi32(alpha, 8));
+		let alpha = _mm256_or_si256(alpha, _mm256_slli_epi32(alpha, 16));
+		let inverse = _mm256_xor_si256(alpha, ones);
+
+		// Unpacking and packing both work within 128-bit lanes, so the order is preserved.
+		let low = _mm256_mullo_epi16(
+			_mm256_unpacklo_epi8(scaled, zero),
+			_mm256_unpacklo_epi8(inverse, zero),
+		);
+		let high = _mm256_mullo_epi16(
+			_mm256_unpackhi_epi8(scaled, zero),
+			_mm256_unpackhi_epi8(inverse, zero),
+		);
+		let low = _mm256_srli_epi16(_mm256_mulhi_epu16(low, reciprocal), 7);
+		let high = _mm256_srli_epi16(_mm256_mulhi_epu16(high, reciprocal), 7);
+
+		let blended = _mm256_adds_epu8(base, _mm256_packus_epi16(low, high));
+		_mm256_storeu_si256(dest.as_mut_ptr().add(i).cast(), blended);
+	}
+	len + sse2(&mut dest[len..], &src[len..], under)
+}
+
+#[target_feature(enable = "sse2")]
+#[allow(clippy::cast_ptr_alignment, clippy::cast_possible_wrap)]
+unsafe fn sse2(dest: &mut [u8], src: &[u8], under: bool) -> usize {
+	let len = dest.len() / 16 * 16;
+	let zero = _mm_setzero_si128();
+	let ones = _mm_set1_epi8(-1);
+	let reciprocal = _mm_set1_epi16(0x8081_u16 as i16);
+	for i in (0..len).step_by(16) {
+		let dest_pixels = _mm_loadu_si128(dest.as_ptr().add(i).cast());
+		let src_pixels = _mm_loadu_si128(src.as_ptr().add(i).cast());
+		let (base, scaled) = if under {
+			(dest_pixels, src_pixels)
+		} else {
+			(src_pixels, dest_pixels)
+		};
+
+		let alpha = _mm_srli_epi32(base, 24);
+		let alpha = _mm_or_si128(alpha, _mm_slli_epi32(alpha, 8));
+		let alpha = _mm_or_si128(alpha, _mm_slli_epi32(alpha, 16));
+		let inverse = _mm_xor_si128(alpha, ones);
+
+		let low = _mm_mullo_epi16(
+			_mm_unpacklo_epi8(scaled, zero),
+			_mm_unpacklo_epi8(inverse, zero),
+		);
+		let high = _mm_mullo_epi16(
+			_mm_unpackhi_epi8(scaled, zero),
+			_mm_unpackhi_epi8(inverse, zero),
+		);
+		let low = _mm_srli_epi16(_mm_mulhi_epu16(low, reciprocal), 7);
+		let high = _mm_srli_epi16(_mm_mulhi_epu16(high, reciprocal), 7);
+
+		let blended = _mm_adds_epu8(base, _mm_packus_epi16(low, high));
+		_mm_storeu_si128(dest.as_mut_ptr().add(i).cast(), blended);
+	}
+	len
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryInto;
+
+	type Kernel = unsafe fn(&mut [u8], &[u8], bool) -> usize;
+
+	/// Pixels covering every pair of a channel value and an alpha, in both orders, followed by some noise.
+	fn pixels() -> Vec<u8> {
+		let mut pixels = Vec::new();
+		for a in 0..=u8::MAX {
+			for b in 0..=u8::MAX {
+				pixels.extend_from_slice(&[a, b, a, b]);
+			}
+		}
+		let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+		pixels.extend((0..1000).map(|_| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state.to_le_bytes()[0]
+		}));
+		pixels
+	}
+
+	/// Checks that `kernel` blends a prefix of pixels exactly like the scalar functions and leaves the rest alone.
+	fn check(kernel: Kernel, block: usize) {
+		let pixels = pixels();
+		let (dest, src) = pixels.split_at(pixels.len() / 2 / 4 * 4);
+		let src = &src[..dest.len()];
+		for under in [true, false] {
+			let scalar = if under {
+				super::super::under_rgba8
+			} else {
+				super::super::over_rgba8
+			};
+			// Also reversed, so that each byte pair is blended both ways.
+			for (dest, src) in [(dest, src), (src, dest)] {
+				for len in (0..=4 * 24).step_by(4).chain(Some(dest.len())) {
+					let mut blended = dest[..len].to_vec();
+					// SAFETY: The caller checked that the kernel's target feature is available.
+					let done = unsafe { kernel(&mut blended, &src[..len], under) };
+					assert_eq!(done, len / block * block);
+
+					let mut expected = dest[..len].to_vec();
+					for (expected, src) in expected[..done]
+						.chunks_exact_mut(4)
+						.zip(src.chunks_exact(4))
+					{
+						scalar(expected, src.try_into().expect("infallible"));
+					}
+					assert!(blended == expected, "under: {}, length: {}", under, len);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn sse2() {
+		if is_x86_feature_detected!("sse2") {
+			check(super::sse2, 16);
+		}
+	}
+
+	#[test]
+	fn avx2() {
+		if is_x86_feature_detected!("avx2") {
+			// Tails shorter than 32 bytes fall through to SSE2.
+			check(super::avx2, 16);
+		}
+	}
+}
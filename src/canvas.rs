@@ -0,0 +1,242 @@
+//! In-memory images that [`Scene`]s are rendered into.
+
+use crate::{scene::Scene, Effect, PixelFormat, Sprite};
+use std::{convert::TryInto, marker::PhantomData, ops::Range};
+
+/// A `width` by `height` pixel image, with rows `stride` bytes apart.
+///
+/// Rendering uses `all_lines_range` `0..height` and a line span of `0..width`, like a [`Frame`](`crate::frame::Frame`) of the same size.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Canvas<P: PixelFormat> {
+	width: usize,
+	height: usize,
+	stride: usize,
+	data: Vec<u8>,
+	_phantom: PhantomData<P>,
+}
+
+impl<P: PixelFormat> Canvas<P> {
+	/// Creates a new transparent (all zero) [`Canvas`] instance with tightly packed rows.
+	///
+	/// # Panics
+	///
+	/// - Iff [`P::PIXEL_STRIDE_BITS`](`PixelFormat::PIXEL_STRIDE_BITS`) isn't a multiple of 8,
+	/// - and also in cases where [`Canvas::with_stride`] would panic.
+	#[must_use]
+	pub fn new(width: usize, height: usize) -> Self {
+		assert_eq!(P::PIXEL_STRIDE_BITS % 8, 0);
+		Self::with_stride(width, height, width * (P::PIXEL_STRIDE_BITS / 8))
+	}
+
+	/// Creates a new transparent (all zero) [`Canvas`] instance with rows `stride` bytes apart.
+	///
+	/// # Panics
+	///
+	/// - Iff [`P::PIXEL_STRIDE_BITS`](`PixelFormat::PIXEL_STRIDE_BITS`) isn't a multiple of 8,
+	/// - iff `stride` is too small to hold `width` pixels,
+	/// - or iff `width` or `height` don't fit into an [`isize`].
+	#[must_use]
+	pub fn with_stride(width: usize, height: usize, stride: usize) -> Self {
+		assert_eq!(P::PIXEL_STRIDE_BITS % 8, 0);
+		assert!(
+			stride >= width * (P::PIXEL_STRIDE_BITS / 8),
+			"`stride` too small"
+		);
+		let _: isize = width.try_into().expect("`width` too large");
+		let _: isize = height.try_into().expect("`height` too large");
+		Self {
+			width,
+			height,
+			stride,
+			data: vec![0; stride * height],
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Gets the width in pixels.
+	#[must_use]
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// Gets the height in pixels.
+	#[must_use]
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// Gets the distance between rows in bytes.
+	#[must_use]
+	pub fn stride(&self) -> usize {
+		self.stride
+	}
+
+	/// Gets the pixel data, row by row.
+	#[must_use]
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Gets the pixel data for modification, row by row.
+	#[must_use]
+	pub fn data_mut(&mut self) -> &mut [u8] {
+		&mut self.data
+	}
+
+	/// Extracts the pixel data, row by row.
+	#[must_use]
+	pub fn into_data(self) -> Vec<u8> {
+		self.data
+	}
+
+	/// Gets the pixels of row `line`, without padding.
+	///
+	/// # Panics
+	///
+	/// Iff `line` is out of range.
+	#[must_use]
+	pub fn row(&self, line: usize) -> &[u8] {
+		assert!(line < self.height, "`line` out of range");
+		let start = line * self.stride;
+		&self.data[start..start + self.width * (P::PIXEL_STRIDE_BITS / 8)]
+	}
+
+	/// Gets the pixels of row `line` for modification, without padding.
+	///
+	/// # Panics
+	///
+	/// Iff `line` is out of range.
+	#[must_use]
+	pub fn row_mut(&mut self, line: usize) -> &mut [u8] {
+		assert!(line < self.height, "`line` out of range");
+		let start = line * self.stride;
+		&mut self.data[start..start + self.width * (P::PIXEL_STRIDE_BITS / 8)]
+	}
+
+	/// Makes all pixels transparent (all zero).
+	pub fn clear(&mut self) {
+		self.data.fill(0);
+	}
+
+	/// Renders all of `scene`, replacing the previous contents.
+	pub fn render<S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>>(&mut self, scene: &Scene<P, S, E>) {
+		self.render_rect(scene, 0..self.height, 0..self.width)
+	}
+
+	/// Renders the part of `scene` within `lines` and `columns`, replacing the previous contents there.
+	///
+	/// Pixels outside the rectangle are left untouched.
+	///
+	/// # Panics
+	///
+	/// Iff `lines` or `columns` extend beyond the canvas.
+	pub fn render_rect<S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>>(
+		&mut self,
+		scene: &Scene<P, S, E>,
+		lines: Range<usize>,
+		columns: Range<usize>,
+	) {
+		assert!(lines.end <= self.height, "`lines` out of range");
+		assert!(columns.end <= self.width, "`columns` out of range");
+
+		let rows = &mut self.data[lines.start * self.stride..];
+		render_rows(
+			scene,
+			self.width,
+			self.height,
+			self.stride,
+			rows,
+			lines,
+			columns,
+		)
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<P: PixelFormat + Sync> Canvas<P> {
+	/// Renders all of `scene` in parallel, replacing the previous contents. See [`Canvas::par_render_rect`].
+	pub fn par_render<S: ?Sized + Sprite<P> + Sync, E: ?Sized + Effect<P> + Sync>(
+		&mut self,
+		scene: &Scene<P, S, E>,
+	) {
+		self.par_render_rect(scene, 0..self.height, 0..self.width)
+	}
+
+	/// Renders the part of `scene` within `lines` and `columns` in parallel, replacing the previous contents there.
+	///
+	/// The rectangle is split into bands of whole lines, which are rendered on the current [`rayon`] thread pool.
+	/// Each line is still rendered independently and with the same arguments as by [`Canvas::render_rect`],
+	/// so the result is identical to it regardless of thread count or scheduling, as long as the drawables are deterministic.
+	///
+	/// Note that `dyn` drawables must be declared [`Sync`], as in `Scene<P, dyn Sprite<P> + Sync, dyn Effect<P> + Sync>`.
+	///
+	/// # Panics
+	///
+	/// Iff `lines` or `columns` extend beyond the canvas.
+	pub fn par_render_rect<S: ?Sized + Sprite<P> + Sync, E: ?Sized + Effect<P> + Sync>(
+		&mut self,
+		scene: &Scene<P, S, E>,
+		lines: Range<usize>,
+		columns: Range<usize>,
+	) {
+		use rayon::{current_num_threads, iter::IndexedParallelIterator, prelude::*};
+
+		assert!(lines.end <= self.height, "`lines` out of range");
+		assert!(columns.end <= self.width, "`columns` out of range");
+		if lines.is_empty() || columns.is_empty() {
+			return;
+		}
+
+		// A few bands per thread, to even out differently expensive parts of the image.
+		let bands = current_num_threads() * 4;
+		#[allow(clippy::manual_div_ceil)]
+		let band_height = (lines.len() + bands - 1) / bands;
+
+		let (width, height, stride) = (self.width, self.height, self.stride);
+		self.data[lines.start * stride..lines.end * stride]
+			.par_chunks_mut(band_height * stride)
+			.enumerate()
+			.for_each(|(i, rows)| {
+				let start = lines.start + i * band_height;
+				let end = (start + band_height).min(lines.end);
+				render_rows(
+					scene,
+					width,
+					height,
+					stride,
+					rows,
+					start..end,
+					columns.clone(),
+				)
+			})
+	}
+}
+
+/// Renders `lines` of `scene` within `columns` into `rows`, which starts with the first of them.
+fn render_rows<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>>(
+	scene: &Scene<P, S, E>,
+	width: usize,
+	height: usize,
+	stride: usize,
+	rows: &mut [u8],
+	lines: Range<usize>,
+	columns: Range<usize>,
+) {
+	let all_lines_range = Some(0..height.try_into().expect("infallible"));
+	let line_span = 0..width.try_into().expect("infallible");
+	let segment_span: Range<isize> =
+		columns.start.try_into().expect("infallible")..columns.end.try_into().expect("infallible");
+	let bytes =
+		columns.start * (P::PIXEL_STRIDE_BITS / 8)..columns.end * (P::PIXEL_STRIDE_BITS / 8);
+	for (i, line) in lines.enumerate() {
+		let row = &mut rows[i * stride..][bytes.clone()];
+		row.fill(0);
+		scene.render_segment(
+			&all_lines_range,
+			line.try_into().expect("infallible"),
+			line_span.clone(),
+			segment_span.clone(),
+			row,
+		)
+	}
+}
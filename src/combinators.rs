@@ -0,0 +1,61 @@
+//! Wrappers that modify how any drawable is rendered.
+
+use crate::{
+	drawables::{MaskMode, Masked},
+	PixelFormat, Position, Sprite,
+};
+use std::{convert::TryInto, ops::Range};
+
+mod clip;
+mod offset;
+mod opacity;
+mod tint;
+pub use clip::Clip;
+pub use offset::Offset;
+pub use opacity::Opacity;
+pub use tint::Tint;
+
+/// Builder-style methods wrapping [`Sprite`]s in combinators. These wrappers also implement [`Effect`](`crate::Effect`) where possible.
+pub trait SpriteExt<P: PixelFormat>: Sprite<P> + Sized {
+	/// Translates this sprite by `position`.
+	fn offset(self, position: Position) -> Offset<Self> {
+		Offset::new(self, position)
+	}
+
+	/// Limits this sprite to a rectangle of `lines` and `columns`.
+	fn clip(self, lines: Range<isize>, columns: Range<isize>) -> Clip<Self> {
+		Clip::new(self, lines, columns)
+	}
+
+	/// Scales this sprite's opacity, where [`u8::MAX`] leaves it unchanged.
+	fn opacity(self, opacity: u8) -> Opacity<Self> {
+		Opacity::new(self, opacity)
+	}
+
+	/// Multiplies this sprite's channels by the premultiplied `color`.
+	fn tint(self, color: [u8; 4]) -> Tint<Self> {
+		Tint::new(self, color)
+	}
+
+	/// Multiplies this sprite's opacity by `mask`'s alpha or luminance.
+	fn masked<M>(self, mask: M, mode: MaskMode) -> Masked<Self, M> {
+		Masked::new(self, mask, mode)
+	}
+}
+impl<P: PixelFormat, T: Sprite<P>> SpriteExt<P> for T {}
+
+/// Renders via `render` into a transparent scratch buffer as long as `data`, then blends each pixel after `map` into `data`.
+fn render_mapped(
+	data: &mut [u8],
+	render: impl FnOnce(&mut [u8]),
+	map: impl Fn([u8; 4]) -> [u8; 4],
+	blend: fn(&mut [u8], &[u8]),
+) {
+	let mut scratch = vec![0; data.len()];
+	render(&mut scratch);
+	for pixel in scratch.chunks_exact_mut(4) {
+		let mapped = map((&*pixel).try_into().expect("infallible"));
+		pixel.copy_from_slice(&mapped);
+	}
+	blend(data, &scratch)
+}
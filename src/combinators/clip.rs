@@ -0,0 +1,100 @@
+use crate::{Effect, Intersect, PixelFormat, Sprite};
+use std::ops::Range;
+
+/// `inner`, limited to a rectangle of `lines` and `columns`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Clip<T> {
+	inner: T,
+	lines: Range<isize>,
+	columns: Range<isize>,
+}
+impl<T> Clip<T> {
+	/// Creates a new [`Clip`] instance.
+	pub fn new(inner: T, lines: Range<isize>, columns: Range<isize>) -> Self {
+		Self {
+			inner,
+			lines,
+			columns,
+		}
+	}
+}
+
+impl<T: Sprite<P>, P: PixelFormat> Sprite<P> for Clip<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.inner
+			.lines(all_lines_range)
+			.intersect(self.lines.clone())
+			.unwrap_or(0..0)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner
+			.line_segment(all_lines_range, line, line_span)
+			.intersect(self.columns.clone())
+			.unwrap_or(0..0)
+	}
+
+	fn opaque_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner
+			.opaque_segment(all_lines_range, line, line_span)
+			.intersect(self.columns.clone())
+			.unwrap_or(0..0)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		self.inner
+			.render(all_lines_range, line, line_span, segment, offset_bits, data)
+	}
+}
+
+impl<T: Effect<P>, P: PixelFormat> Effect<P> for Clip<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.inner
+			.lines(all_lines_range)
+			.intersect(self.lines.clone())
+			.unwrap_or(0..0)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner
+			.line_segment(all_lines_range, line, line_span)
+			.intersect(self.columns.clone())
+			.unwrap_or(0..0)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		self.inner
+			.render(all_lines_range, line, line_span, segment, offset_bits, data)
+	}
+}
@@ -0,0 +1,133 @@
+use crate::{Effect, PixelFormat, Position, Sprite};
+use std::ops::Range;
+
+/// `inner`, translated by `position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Offset<T> {
+	inner: T,
+	position: Position,
+}
+impl<T> Offset<T> {
+	/// Creates a new [`Offset`] instance.
+	pub fn new(inner: T, position: Position) -> Self {
+		Self { inner, position }
+	}
+
+	fn inner_lines_range(&self, all_lines_range: Option<Range<isize>>) -> Option<Range<isize>> {
+		all_lines_range.map(|all_lines_range| to_inner(all_lines_range, self.position.y))
+	}
+}
+
+/// Translates `range` from `inner`'s coordinates, saturating so that unbounded ranges stay unbounded.
+fn to_outer(range: Range<isize>, distance: isize) -> Range<isize> {
+	range.start.saturating_add(distance)..range.end.saturating_add(distance)
+}
+
+/// Translates `range` into `inner`'s coordinates, saturating so that unbounded ranges stay unbounded.
+fn to_inner(range: Range<isize>, distance: isize) -> Range<isize> {
+	range.start.saturating_sub(distance)..range.end.saturating_sub(distance)
+}
+
+impl<T: Sprite<P>, P: PixelFormat> Sprite<P> for Offset<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		to_outer(
+			self.inner.lines(self.inner_lines_range(all_lines_range)),
+			self.position.y,
+		)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		to_outer(
+			self.inner.line_segment(
+				self.inner_lines_range(all_lines_range),
+				line.saturating_sub(self.position.y),
+				to_inner(line_span, self.position.x),
+			),
+			self.position.x,
+		)
+	}
+
+	fn opaque_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		to_outer(
+			self.inner.opaque_segment(
+				self.inner_lines_range(all_lines_range),
+				line.saturating_sub(self.position.y),
+				to_inner(line_span, self.position.x),
+			),
+			self.position.x,
+		)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		self.inner.render(
+			self.inner_lines_range(all_lines_range),
+			line - self.position.y,
+			to_inner(line_span, self.position.x),
+			to_inner(segment, self.position.x),
+			offset_bits,
+			data,
+		)
+	}
+}
+
+impl<T: Effect<P>, P: PixelFormat> Effect<P> for Offset<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		to_outer(
+			self.inner.lines(self.inner_lines_range(all_lines_range)),
+			self.position.y,
+		)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		to_outer(
+			self.inner.line_segment(
+				self.inner_lines_range(all_lines_range),
+				line.saturating_sub(self.position.y),
+				to_inner(line_span, self.position.x),
+			),
+			self.position.x,
+		)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		self.inner.render(
+			self.inner_lines_range(all_lines_range),
+			line - self.position.y,
+			to_inner(line_span, self.position.x),
+			to_inner(segment, self.position.x),
+			offset_bits,
+			data,
+		)
+	}
+}
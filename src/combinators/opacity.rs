@@ -0,0 +1,112 @@
+use super::render_mapped;
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, Sprite};
+use std::{convert::TryInto, ops::Range};
+
+/// `inner`, with its opacity scaled by a constant `opacity`, where [`u8::MAX`] leaves it unchanged.
+///
+/// As [`Effect`], this fades between the pixels below and `inner` rendered onto them, so it also applies to effects that read or replace those pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Opacity<T> {
+	inner: T,
+	opacity: u8,
+}
+impl<T> Opacity<T> {
+	/// Creates a new [`Opacity`] instance.
+	pub fn new(inner: T, opacity: u8) -> Self {
+		Self { inner, opacity }
+	}
+}
+
+impl<T: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for Opacity<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.inner.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn opaque_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		if self.opacity == u8::MAX {
+			self.inner.opaque_segment(all_lines_range, line, line_span)
+		} else {
+			0..0
+		}
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_mapped(
+			data,
+			|scratch| {
+				self.inner
+					.render(all_lines_range, line, line_span, segment, 0, scratch)
+			},
+			|pixel| blend::scale_rgba8(pixel, self.opacity),
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<T: Effect<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for Opacity<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.inner.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		// Effects may read what's below them, so they are faded by their change to `data` rather than by their output.
+		let mut rendered = data.to_vec();
+		self.inner
+			.render(all_lines_range, line, line_span, segment, 0, &mut rendered);
+		for (before, after) in data.iter_mut().zip(rendered) {
+			*before = lerp(*before, after, self.opacity);
+		}
+	}
+}
+
+/// Interpolates from `from` to `to`, where [`u8::MAX`] means all the way.
+fn lerp(from: u8, to: u8, weight: u8) -> u8 {
+	let weight = u16::from(weight);
+	((u16::from(from) * (u16::from(u8::MAX) - weight) + u16::from(to) * weight + 127)
+		/ u16::from(u8::MAX))
+	.try_into()
+	.expect("infallible")
+}
@@ -0,0 +1,117 @@
+use super::render_mapped;
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, Sprite};
+use std::{convert::TryInto, ops::Range};
+
+/// `inner`, with each channel multiplied by the premultiplied `color`, where opaque white leaves it unchanged.
+///
+/// As [`Effect`], `inner` is rendered onto transparency, tinted and then blended over the pixels below.
+/// That's only equivalent for effects that are drawn source-over, like this crate's drawables.
+/// Effects that read or replace the pixels below them see transparency instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tint<T> {
+	inner: T,
+	color: [u8; 4],
+}
+impl<T> Tint<T> {
+	/// Creates a new [`Tint`] instance.
+	pub fn new(inner: T, color: [u8; 4]) -> Self {
+		Self { inner, color }
+	}
+}
+
+impl<T: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for Tint<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.inner.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn opaque_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		if self.color[3] == u8::MAX {
+			self.inner.opaque_segment(all_lines_range, line, line_span)
+		} else {
+			0..0
+		}
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_mapped(
+			data,
+			|scratch| {
+				self.inner
+					.render(all_lines_range, line, line_span, segment, 0, scratch)
+			},
+			|pixel| multiply(pixel, self.color),
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<T: Effect<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for Tint<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.inner.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.inner.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_mapped(
+			data,
+			|scratch| {
+				self.inner
+					.render(all_lines_range, line, line_span, segment, 0, scratch)
+			},
+			|pixel| multiply(pixel, self.color),
+			blend::over_rgba8_pixels,
+		)
+	}
+}
+
+fn multiply(pixel: [u8; 4], color: [u8; 4]) -> [u8; 4] {
+	let mut multiplied = [0; 4];
+	for ((multiplied, pixel), color) in multiplied.iter_mut().zip(&pixel).zip(&color) {
+		*multiplied = (u16::from(*pixel) * u16::from(*color) / u16::from(u8::MAX))
+			.try_into()
+			.expect("infallible");
+	}
+	multiplied
+}
@@ -0,0 +1,62 @@
+//! Tracking of fully opaque pixels in a line, as sprites are drawn front to back.
+
+use std::{
+	cmp::{max, min},
+	iter,
+	ops::Range,
+};
+
+/// Sorted, disjoint and non-adjacent runs of fully opaque pixels.
+#[derive(Debug, Default)]
+pub(crate) struct Coverage {
+	runs: Vec<Range<isize>>,
+}
+impl Coverage {
+	pub(crate) fn new() -> Self {
+		Self::default()
+	}
+
+	/// Checks whether all of `segment` is opaque.
+	pub(crate) fn covers(&self, segment: &Range<isize>) -> bool {
+		let i = self.runs.partition_point(|run| run.end <= segment.start);
+		segment.is_empty()
+			|| matches!(self.runs.get(i), Some(run) if run.start <= segment.start && segment.end <= run.end)
+	}
+
+	/// Gets the parts of `segment` that aren't opaque yet, in ascending order.
+	pub(crate) fn gaps(&self, segment: Range<isize>) -> Vec<Range<isize>> {
+		let mut gaps = Vec::new();
+		let mut start = segment.start;
+		let first = self.runs.partition_point(|run| run.end <= segment.start);
+		for run in &self.runs[first..] {
+			if run.start >= segment.end {
+				break;
+			}
+			if run.start > start {
+				gaps.push(start..run.start);
+			}
+			start = max(start, run.end);
+		}
+		if start < segment.end {
+			gaps.push(start..segment.end);
+		}
+		gaps
+	}
+
+	/// Marks `run` as opaque.
+	pub(crate) fn cover(&mut self, run: Range<isize>) {
+		if run.is_empty() {
+			return;
+		}
+
+		// Merges with overlapping and adjacent runs.
+		let first = self.runs.partition_point(|other| other.end < run.start);
+		let last = self.runs.partition_point(|other| other.start <= run.end);
+		let merged = if first < last {
+			min(run.start, self.runs[first].start)..max(run.end, self.runs[last - 1].end)
+		} else {
+			run
+		};
+		self.runs.splice(first..last, iter::once(merged));
+	}
+}
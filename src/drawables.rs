@@ -1,8 +1,42 @@
 //! Instances that can be rendered line by line.
 
 mod bitmap;
+mod bitmap_text;
+mod circle;
 mod color_clip;
+mod conic_gradient;
+mod ellipse;
+mod fill;
+mod glyphs;
+mod gradient;
+mod linear_gradient;
+mod masked;
+mod noise;
+mod outline_text;
+mod radial_gradient;
+mod rounded_rect;
+mod shader;
+mod shape;
+mod solid;
+mod stroke;
+mod text_block;
 mod zoomed_bitmap;
 pub use bitmap::Bitmap;
+pub use bitmap_text::BitmapText;
+pub use circle::Circle;
 pub use color_clip::ColorClip;
+pub use conic_gradient::ConicGradient;
+pub use ellipse::Ellipse;
+pub use fill::{Fill, FillRule};
+pub use gradient::{ColorStop, Spread};
+pub use linear_gradient::LinearGradient;
+pub use masked::{MaskMode, Masked};
+pub use noise::{Noise, NoiseKind};
+pub use outline_text::OutlineText;
+pub use radial_gradient::RadialGradient;
+pub use rounded_rect::RoundedRect;
+pub use shader::{PixelShader, SegmentShader};
+pub use solid::Solid;
+pub use stroke::{LineCap, LineJoin, Stroke, StrokeStyle};
+pub use text_block::{Alignment, TextBlock, TextStyle};
 pub use zoomed_bitmap::ZoomedBitmap;
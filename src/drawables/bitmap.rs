@@ -1,6 +1,6 @@
-use crate::{pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
 use std::{convert::TryInto, marker::PhantomData, ops::Range};
-use tap::{Conv, TryConv};
+use tap::TryConv;
 
 /// A simple bitmap sprite, drawn with saturating addition.
 pub struct Bitmap<'a, P: PixelFormat> {
@@ -17,7 +17,7 @@ impl<'a> Bitmap<'a, RgbaNoPadding<8>> {
 	#[must_use]
 	pub fn new(width: usize, data: &'a [u8]) -> Self {
 		assert_eq!(
-			data.len() % (width * RgbaNoPadding::<8>::PIXEL_STRIDE_BITS * 8),
+			data.len() % (width * RgbaNoPadding::<8>::PIXEL_STRIDE_BITS / 8),
 			0
 		);
 		Self {
@@ -68,25 +68,8 @@ impl Sprite<RgbaNoPadding<8>> for Bitmap<'_, RgbaNoPadding<8>> {
 		assert!(segment.end.try_conv::<usize>().expect("infallible") <= self.width);
 		assert_eq!(segment.len() * PIXEL_BYTES, data.len());
 
-		for (src, dest) in self
-			.data
-			.chunks_exact(PIXEL_BYTES)
-			.skip(line * self.width)
-			.skip(segment.start)
-			.take(segment.len())
-			.zip(data.chunks_exact_mut(PIXEL_BYTES))
-		{
-			let dest_alpha = dest[3];
-
-			for (src, dest) in src.iter().zip(dest) {
-				*dest = (*dest).saturating_add(
-					((*src).conv::<u16>() * (u8::MAX - dest_alpha).conv::<u16>()
-						/ u8::MAX.conv::<u16>())
-					.try_conv::<u8>()
-					.expect("infallible"),
-				);
-			}
-		}
+		let start = (line * self.width + segment.start) * PIXEL_BYTES;
+		blend::under_rgba8_pixels(data, &self.data[start..start + data.len()])
 	}
 }
 
@@ -131,24 +114,7 @@ impl Effect<RgbaNoPadding<8>> for Bitmap<'_, RgbaNoPadding<8>> {
 		assert!(segment.end.try_conv::<usize>().expect("infallible") <= self.width);
 		assert_eq!(segment.len() * PIXEL_BYTES, data.len());
 
-		for (src, dest) in self
-			.data
-			.chunks_exact(PIXEL_BYTES)
-			.skip(line * self.width)
-			.skip(segment.start)
-			.take(segment.len())
-			.zip(data.chunks_exact_mut(PIXEL_BYTES))
-		{
-			let src_alpha = src[3];
-
-			for (src, dest) in src.iter().zip(dest) {
-				*dest = src.saturating_add(
-					((*dest).conv::<u16>() * (u8::MAX - src_alpha).conv::<u16>()
-						/ u8::MAX.conv::<u16>())
-					.try_conv::<u8>()
-					.expect("infallible"),
-				);
-			}
-		}
+		let start = (line * self.width + segment.start) * PIXEL_BYTES;
+		blend::over_rgba8_pixels(data, &self.data[start..start + data.len()])
 	}
 }
@@ -0,0 +1,122 @@
+use super::glyphs::{self, PlacedGlyph};
+use crate::{
+	blend,
+	pixel_formats::RgbaNoPadding,
+	raster::render_coverage,
+	text::{BitmapFont, BitmapGlyph},
+	Effect, Sprite,
+};
+use std::ops::Range;
+
+/// Text set in a [`BitmapFont`] and filled with `paint`.
+///
+/// The first line's top edge is at `y = 0` and lines start at `x = 0`. Glyphs aren't kerned.
+/// `paint` shares the text's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitmapText<'a, F> {
+	glyphs: Vec<PlacedGlyph<&'a BitmapGlyph>>,
+	lines: Range<isize>,
+	paint: F,
+}
+
+impl<'a, F> BitmapText<'a, F> {
+	/// Creates a new [`BitmapText`] instance, with `'\n'` starting a new line.
+	pub fn new(font: &'a BitmapFont, text: &str, paint: F) -> Self {
+		let mut glyphs = Vec::new();
+		let mut y = 0;
+		for line in text.split('\n') {
+			let mut x = 0;
+			for character in line.trim_end_matches('\r').chars() {
+				if let Some(glyph) = font.glyph(character) {
+					glyphs.push(PlacedGlyph { x, y, glyph });
+					x += glyph.advance;
+				}
+			}
+			y += font.line_height();
+		}
+
+		let lines = glyphs::lines(&glyphs, 0..y);
+
+		Self {
+			glyphs,
+			lines,
+			paint,
+		}
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for BitmapText<'_, F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines.clone()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		glyphs::line_segment(&self.glyphs, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.paint,
+			&glyphs::coverage(&self.glyphs, line, segment.clone()),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for BitmapText<'_, F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines.clone()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		glyphs::line_segment(&self.glyphs, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.paint,
+			&glyphs::coverage(&self.glyphs, line, segment.clone()),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
@@ -0,0 +1,101 @@
+use super::{ellipse::EllipseOutline, shape};
+use crate::{blend, geometry::Point, pixel_formats::RgbaNoPadding, Effect, Sprite};
+use std::ops::Range;
+
+/// A circle filled with `paint`, with anti-aliased edges.
+///
+/// `paint` shares this shape's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle<F> {
+	outline: EllipseOutline,
+	paint: F,
+}
+impl<F> Circle<F> {
+	/// Creates a new [`Circle`] instance.
+	pub fn new(center: Point, radius: f32, paint: F) -> Self {
+		Self {
+			outline: EllipseOutline {
+				center,
+				radius_x: radius,
+				radius_y: radius,
+			},
+			paint,
+		}
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for Circle<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		shape::lines(&self.outline)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		shape::line_segment(&self.outline, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		shape::render(
+			&self.outline,
+			&self.paint,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for Circle<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		shape::lines(&self.outline)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		shape::line_segment(&self.outline, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		shape::render(
+			&self.outline,
+			&self.paint,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
@@ -1,8 +1,6 @@
 use std::{marker::PhantomData, ops::Range};
 
-use tap::{Conv, TryConv};
-
-use crate::{pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
 
 /// A flat-coloured dynamically masked sprite.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -54,6 +52,19 @@ impl<
 		(self.segments)(all_lines_range, line, line_span)
 	}
 
+	fn opaque_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		if self.color[3] == u8::MAX {
+			(self.segments)(all_lines_range, line, line_span)
+		} else {
+			0..0
+		}
+	}
+
 	fn render(
 		&self,
 		_all_lines_range: Option<Range<isize>>,
@@ -65,18 +76,7 @@ impl<
 	) {
 		assert_eq!(offset_bits, 0);
 
-		for dest in data.chunks_exact_mut(4) {
-			let dest_alpha = dest[3];
-
-			for (src, dest) in self.color.iter().zip(dest) {
-				*dest = (*dest).saturating_add(
-					((*src).conv::<u16>() * (u8::MAX - dest_alpha).conv::<u16>()
-						/ u8::MAX.conv::<u16>())
-					.try_conv::<u8>()
-					.expect("infallible"),
-				);
-			}
-		}
+		blend::under_rgba8_color(data, self.color)
 	}
 }
 
@@ -109,17 +109,6 @@ impl<
 	) {
 		assert_eq!(offset_bits, 0);
 
-		for dest in data.chunks_exact_mut(4) {
-			let src_alpha = self.color[3];
-
-			for (src, dest) in self.color.iter().zip(dest) {
-				*dest = src.saturating_add(
-					((*dest).conv::<u16>() * (u8::MAX - src_alpha).conv::<u16>()
-						/ u8::MAX.conv::<u16>())
-					.try_conv::<u8>()
-					.expect("infallible"),
-				);
-			}
-		}
+		blend::over_rgba8_color(data, self.color)
 	}
 }
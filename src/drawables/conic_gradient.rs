@@ -0,0 +1,133 @@
+use super::gradient::{ColorStop, Ramp, Spread};
+use crate::{geometry::Point, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use std::{f32::consts::PI, marker::PhantomData, ops::Range};
+
+/// A dynamically masked conic (sweep) gradient around a centre point.
+///
+/// Offsets run from `0.0` at `start_angle` to `1.0` after one full clockwise turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicGradient<
+	P: PixelFormat,
+	L: Fn(Option<Range<isize>>) -> Range<isize>,
+	S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+> {
+	lines: L,
+	segments: S,
+	center: Point,
+	start_angle: f32,
+	ramp: Ramp,
+	_phantom: PhantomData<P>,
+}
+
+impl<
+		P: PixelFormat,
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> ConicGradient<P, L, S>
+{
+	/// Creates a new [`ConicGradient`] instance.
+	///
+	/// `start_angle` is in radians, clockwise from the positive x axis. `stops` don't have to be sorted.
+	///
+	/// # Panics
+	///
+	/// Iff any stop offset is NaN.
+	pub fn new(
+		lines: L,
+		segments: S,
+		center: Point,
+		start_angle: f32,
+		stops: impl IntoIterator<Item = ColorStop>,
+	) -> Self {
+		Self {
+			lines,
+			segments,
+			center,
+			start_angle,
+			ramp: Ramp::new(stops, Spread::Pad),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Enables ordered dithering, which avoids visible banding in shallow gradients.
+	#[must_use]
+	pub fn dithered(self) -> Self {
+		Self {
+			ramp: self.ramp.dithered(),
+			..self
+		}
+	}
+
+	fn t(&self, point: Point) -> f32 {
+		let point = point - self.center;
+		((point.y.atan2(point.x) - self.start_angle) / (2. * PI)).rem_euclid(1.)
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Sprite<RgbaNoPadding<8>> for ConicGradient<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_under(line, segment, data, |point| self.t(point))
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Effect<RgbaNoPadding<8>> for ConicGradient<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_over(line, segment, data, |point| self.t(point))
+	}
+}
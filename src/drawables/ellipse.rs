@@ -0,0 +1,128 @@
+use super::shape::{self, ConvexOutline};
+use crate::{blend, geometry::Point, pixel_formats::RgbaNoPadding, Effect, Sprite};
+use std::ops::Range;
+
+/// An axis-aligned ellipse filled with `paint`, with anti-aliased edges.
+///
+/// `paint` shares this shape's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipse<F> {
+	outline: EllipseOutline,
+	paint: F,
+}
+impl<F> Ellipse<F> {
+	/// Creates a new [`Ellipse`] instance.
+	pub fn new(center: Point, radius_x: f32, radius_y: f32, paint: F) -> Self {
+		Self {
+			outline: EllipseOutline {
+				center,
+				radius_x,
+				radius_y,
+			},
+			paint,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct EllipseOutline {
+	pub(super) center: Point,
+	pub(super) radius_x: f32,
+	pub(super) radius_y: f32,
+}
+impl ConvexOutline for EllipseOutline {
+	fn top_bottom(&self) -> (f32, f32) {
+		(self.center.y - self.radius_y, self.center.y + self.radius_y)
+	}
+
+	fn widest(&self) -> f32 {
+		self.center.y
+	}
+
+	fn span(&self, y: f32) -> Option<(f32, f32)> {
+		let dy = (y - self.center.y) / self.radius_y;
+		let squared = 1. - dy * dy;
+		if squared >= 0. {
+			let half_width = self.radius_x * squared.sqrt();
+			Some((self.center.x - half_width, self.center.x + half_width))
+		} else {
+			None
+		}
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for Ellipse<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		shape::lines(&self.outline)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		shape::line_segment(&self.outline, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		shape::render(
+			&self.outline,
+			&self.paint,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for Ellipse<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		shape::lines(&self.outline)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		shape::line_segment(&self.outline, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		shape::render(
+			&self.outline,
+			&self.paint,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
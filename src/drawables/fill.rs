@@ -0,0 +1,135 @@
+use crate::{
+	blend,
+	geometry::Path,
+	pixel_formats::RgbaNoPadding,
+	raster::{render_coverage, Polygons},
+	Effect, Sprite,
+};
+use std::ops::Range;
+
+/// Decides which areas enclosed by a self-overlapping outline are inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+	/// Inside iff the outline winds around a point at all, in either direction.
+	NonZero,
+	/// Inside iff the outline crosses any ray from a point an odd number of times.
+	EvenOdd,
+}
+impl FillRule {
+	fn contains(self, winding: i32) -> bool {
+		match self {
+			FillRule::NonZero => winding != 0,
+			FillRule::EvenOdd => winding % 2 != 0,
+		}
+	}
+}
+
+/// A filled [`Path`], rasterized with anti-aliasing and filled with `paint`.
+///
+/// All subpaths are implicitly closed.
+/// `paint` shares the path's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill<F> {
+	polygons: Polygons,
+	rule: FillRule,
+	paint: F,
+}
+impl<F> Fill<F> {
+	/// Creates a new [`Fill`] instance.
+	///
+	/// Curves in `path` are flattened to polygons that deviate from them by at most `tolerance` pixels.
+	/// `0.25` is a good default.
+	pub fn new(path: &Path, tolerance: f32, fill_rule: FillRule, paint: F) -> Self {
+		let mut polygons = Polygons::new();
+		for (points, _closed) in path.flatten(tolerance) {
+			polygons.add_polygon(&points);
+		}
+		Self {
+			polygons,
+			rule: fill_rule,
+			paint,
+		}
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for Fill<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.polygons.lines()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		self.polygons.line_segment(line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		let rule = self.rule;
+		render_coverage(
+			&self.paint,
+			&self
+				.polygons
+				.coverage(line, segment.clone(), |winding| rule.contains(winding)),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for Fill<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.polygons.lines()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		self.polygons.line_segment(line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		let rule = self.rule;
+		render_coverage(
+			&self.paint,
+			&self
+				.polygons
+				.coverage(line, segment.clone(), |winding| rule.contains(winding)),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
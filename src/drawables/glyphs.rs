@@ -0,0 +1,93 @@
+use crate::{text::BitmapGlyph, Intersect};
+use std::{
+	cmp::{max, min},
+	convert::TryInto,
+	ops::{Deref, Range},
+};
+
+/// A glyph's coverage bitmap placed at a pen position.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct PlacedGlyph<G> {
+	pub(super) x: isize,
+	pub(super) y: isize,
+	pub(super) glyph: G,
+}
+impl<G: Deref<Target = BitmapGlyph>> PlacedGlyph<G> {
+	fn rows(&self) -> Range<isize> {
+		let top = self.y + self.glyph.top;
+		let height: isize = self
+			.glyph
+			.height()
+			.try_into()
+			.expect("glyph height out of range");
+		top..top + height
+	}
+
+	fn columns(&self) -> Range<isize> {
+		let left = self.x + self.glyph.left;
+		let width: isize = self
+			.glyph
+			.width
+			.try_into()
+			.expect("glyph width out of range");
+		left..left + width
+	}
+}
+
+/// Gets the union of `lines` and all glyphs' rows.
+pub(super) fn lines<G: Deref<Target = BitmapGlyph>>(
+	glyphs: &[PlacedGlyph<G>],
+	lines: Range<isize>,
+) -> Range<isize> {
+	glyphs
+		.iter()
+		.map(PlacedGlyph::rows)
+		.filter(|rows| !rows.is_empty())
+		.fold(lines, |lines, rows| {
+			min(lines.start, rows.start)..max(lines.end, rows.end)
+		})
+}
+
+pub(super) fn line_segment<G: Deref<Target = BitmapGlyph>>(
+	glyphs: &[PlacedGlyph<G>],
+	line: isize,
+) -> Range<isize> {
+	glyphs
+		.iter()
+		.filter(|glyph| glyph.rows().contains(&line))
+		.map(PlacedGlyph::columns)
+		.filter(|columns| !columns.is_empty())
+		.reduce(|a, b| min(a.start, b.start)..max(a.end, b.end))
+		.unwrap_or(0..0)
+}
+
+/// Sums the coverage of all glyphs on `line` within `segment`, saturating.
+pub(super) fn coverage<G: Deref<Target = BitmapGlyph>>(
+	glyphs: &[PlacedGlyph<G>],
+	line: isize,
+	segment: Range<isize>,
+) -> Vec<u8> {
+	let mut coverage = vec![0_u8; segment.len()];
+	for glyph in glyphs {
+		let rows = glyph.rows();
+		if !rows.contains(&line) {
+			continue;
+		}
+
+		let columns = glyph.columns();
+		if let Some(overlap) = columns.clone().intersect(segment.clone()) {
+			let row: usize = (line - rows.start).try_into().expect("infallible");
+			let source: usize = (overlap.start - columns.start)
+				.try_into()
+				.expect("infallible");
+			let target: usize = (overlap.start - segment.start)
+				.try_into()
+				.expect("infallible");
+			let source = &glyph.glyph.coverage[row * glyph.glyph.width + source..][..overlap.len()];
+			for (coverage, source) in coverage[target..].iter_mut().zip(source) {
+				*coverage = coverage.saturating_add(*source);
+			}
+		}
+	}
+	coverage
+}
@@ -0,0 +1,161 @@
+use crate::{
+	blend,
+	geometry::{pixel_center, Point},
+};
+use std::ops::Range;
+
+/// A colour stop along a gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+	/// Position along the gradient, usually in `0.0..=1.0`.
+	pub offset: f32,
+	/// Premultiplied RGBA colour at [`offset`](`ColorStop::offset`).
+	pub color: [u8; 4],
+}
+
+/// How a gradient continues outside of its `0.0..=1.0` range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Spread {
+	/// Extends the colours of the first and last stop.
+	Pad,
+	/// Restarts the gradient at each whole number.
+	Repeat,
+	/// Alternates between the gradient and its mirror image.
+	Reflect,
+}
+
+/// The colour stops and spread of a gradient, shared by all gradient drawables.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Ramp {
+	stops: Vec<ColorStop>,
+	spread: Spread,
+	dither: bool,
+}
+impl Ramp {
+	/// # Panics
+	///
+	/// Iff any stop offset is NaN.
+	pub(super) fn new(stops: impl IntoIterator<Item = ColorStop>, spread: Spread) -> Self {
+		let mut stops: Vec<_> = stops.into_iter().collect();
+		stops.sort_by(|a, b| {
+			a.offset
+				.partial_cmp(&b.offset)
+				.expect("gradient stop offsets must not be NaN")
+		});
+		Self {
+			stops,
+			spread,
+			dither: false,
+		}
+	}
+
+	pub(super) fn dithered(self) -> Self {
+		Self {
+			dither: true,
+			..self
+		}
+	}
+
+	/// Gets the unquantised colour at `t`, which is transparent for NaN.
+	///
+	/// Infinite `t` isn't spread and takes the first or last stop's colour.
+	fn color(&self, t: f32) -> [f32; 4] {
+		if t.is_nan() {
+			return [0.; 4];
+		}
+
+		let t = match self.spread {
+			_ if t.is_infinite() => t,
+			Spread::Pad => t,
+			Spread::Repeat => t - t.floor(),
+			Spread::Reflect => {
+				let t = t.rem_euclid(2.);
+				if t > 1. {
+					2. - t
+				} else {
+					t
+				}
+			}
+		};
+
+		let next = self.stops.iter().position(|stop| stop.offset > t);
+		let (before, after) = match next {
+			None => match self.stops.last() {
+				Some(last) => (last, last),
+				None => return [0.; 4],
+			},
+			Some(0) => (&self.stops[0], &self.stops[0]),
+			Some(next) => (&self.stops[next - 1], &self.stops[next]),
+		};
+
+		let weight = if after.offset > before.offset {
+			(t - before.offset) / (after.offset - before.offset)
+		} else {
+			0.
+		};
+		let mut color = [0.; 4];
+		for ((color, before), after) in color.iter_mut().zip(&before.color).zip(&after.color) {
+			*color = f32::from(*before) + (f32::from(*after) - f32::from(*before)) * weight;
+		}
+		color
+	}
+
+	/// Rounds `color` to whole channel values, using an ordered dither pattern if enabled.
+	///
+	/// All channels are rounded with the same threshold, so premultiplication stays valid.
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	fn quantize(&self, x: isize, y: isize, color: [f32; 4]) -> [u8; 4] {
+		const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+		let threshold = if self.dither {
+			(f32::from(BAYER[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize]) + 0.5) / 16.
+		} else {
+			0.5
+		};
+
+		let mut quantized = [0; 4];
+		for (quantized, color) in quantized.iter_mut().zip(&color) {
+			*quantized = (color + threshold).clamp(0., 255.) as u8;
+		}
+		quantized
+	}
+
+	/// Renders the given segment of the given line under `data`, with `t` mapping pixel centres to gradient positions.
+	pub(super) fn render_under(
+		&self,
+		line: isize,
+		segment: Range<isize>,
+		data: &mut [u8],
+		t: impl Fn(Point) -> f32,
+	) {
+		assert_eq!(segment.len() * 4, data.len());
+
+		blend::under_rgba8_iter(data, self.pixels(line, segment, t))
+	}
+
+	/// Renders the given segment of the given line over `data`, with `t` mapping pixel centres to gradient positions.
+	pub(super) fn render_over(
+		&self,
+		line: isize,
+		segment: Range<isize>,
+		data: &mut [u8],
+		t: impl Fn(Point) -> f32,
+	) {
+		assert_eq!(segment.len() * 4, data.len());
+
+		blend::over_rgba8_iter(data, self.pixels(line, segment, t))
+	}
+
+	fn pixels<'a>(
+		&'a self,
+		line: isize,
+		segment: Range<isize>,
+		t: impl Fn(Point) -> f32 + 'a,
+	) -> impl Iterator<Item = [u8; 4]> + 'a {
+		let y = pixel_center(line);
+		segment.map(move |x| {
+			let color = self.color(t(Point::new(pixel_center(x), y)));
+			self.quantize(x, line, color)
+		})
+	}
+}
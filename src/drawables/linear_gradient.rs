@@ -0,0 +1,140 @@
+use super::gradient::{ColorStop, Ramp, Spread};
+use crate::{geometry::Point, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use std::{marker::PhantomData, ops::Range};
+
+/// A dynamically masked linear gradient along an arbitrary axis.
+///
+/// Colours are interpolated (premultiplied) from `start` to `end` and constant perpendicular to that axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient<
+	P: PixelFormat,
+	L: Fn(Option<Range<isize>>) -> Range<isize>,
+	S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+> {
+	lines: L,
+	segments: S,
+	start: Point,
+	end: Point,
+	ramp: Ramp,
+	_phantom: PhantomData<P>,
+}
+
+impl<
+		P: PixelFormat,
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> LinearGradient<P, L, S>
+{
+	/// Creates a new [`LinearGradient`] instance.
+	///
+	/// `stops` don't have to be sorted. If `start` and `end` coincide, the last stop's colour is used throughout.
+	///
+	/// # Panics
+	///
+	/// Iff any stop offset is NaN.
+	pub fn new(
+		lines: L,
+		segments: S,
+		start: Point,
+		end: Point,
+		stops: impl IntoIterator<Item = ColorStop>,
+		spread: Spread,
+	) -> Self {
+		Self {
+			lines,
+			segments,
+			start,
+			end,
+			ramp: Ramp::new(stops, spread),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Enables ordered dithering, which avoids visible banding in shallow gradients.
+	#[must_use]
+	pub fn dithered(self) -> Self {
+		Self {
+			ramp: self.ramp.dithered(),
+			..self
+		}
+	}
+
+	/// Projects `point` onto the axis, or returns infinity (the last stop) if the axis is degenerate.
+	fn t(&self, point: Point) -> f32 {
+		let axis = self.end - self.start;
+		let length_squared = axis.dot(axis);
+		if length_squared == 0. {
+			f32::INFINITY
+		} else {
+			(point - self.start).dot(axis) / length_squared
+		}
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Sprite<RgbaNoPadding<8>> for LinearGradient<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_under(line, segment, data, |point| self.t(point))
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Effect<RgbaNoPadding<8>> for LinearGradient<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_over(line, segment, data, |point| self.t(point))
+	}
+}
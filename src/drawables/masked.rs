@@ -0,0 +1,169 @@
+use crate::{
+	blend,
+	pixel_formats::RgbaNoPadding,
+	raster::{render_coverage, render_paint},
+	Effect, Intersect, Sprite,
+};
+use std::{convert::TryInto, ops::Range};
+
+/// Which channels of a [`Masked`] instance's mask make its content visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaskMode {
+	/// The mask's opacity.
+	Alpha,
+	/// The mask's (Rec. 709) luminance, times its opacity.
+	Luminance,
+}
+
+/// `content`, with its opacity multiplied by `mask`'s alpha or luminance.
+///
+/// Both share the same coordinate system, and `content` is only visible where they overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Masked<S, M> {
+	content: S,
+	mask: M,
+	mode: MaskMode,
+}
+impl<S, M> Masked<S, M> {
+	/// Creates a new [`Masked`] instance.
+	pub fn new(content: S, mask: M, mode: MaskMode) -> Self {
+		Self {
+			content,
+			mask,
+			mode,
+		}
+	}
+}
+
+impl<S: Sprite<RgbaNoPadding<8>>, M: Sprite<RgbaNoPadding<8>>> Masked<S, M> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.content
+			.lines(all_lines_range.clone())
+			.intersect(self.mask.lines(all_lines_range))
+			.unwrap_or(0..0)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.content
+			.line_segment(all_lines_range.clone(), line, line_span.clone())
+			.intersect(self.mask.line_segment(all_lines_range, line, line_span))
+			.unwrap_or(0..0)
+	}
+
+	fn coverage(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+	) -> Vec<u8> {
+		let mask = render_paint(&self.mask, all_lines_range, line, line_span, segment);
+		mask.chunks_exact(4)
+			.map(|pixel| match self.mode {
+				MaskMode::Alpha => pixel[3],
+				// Premultiplied channels already include the opacity.
+				MaskMode::Luminance => ((u32::from(pixel[0]) * 54
+					+ u32::from(pixel[1]) * 183
+					+ u32::from(pixel[2]) * 19)
+					/ 256)
+					.try_into()
+					.expect("infallible"),
+			})
+			.collect()
+	}
+}
+
+impl<S: Sprite<RgbaNoPadding<8>>, M: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>>
+	for Masked<S, M>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.content,
+			&self.coverage(
+				all_lines_range.clone(),
+				line,
+				line_span.clone(),
+				segment.clone(),
+			),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<S: Sprite<RgbaNoPadding<8>>, M: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>>
+	for Masked<S, M>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.content,
+			&self.coverage(
+				all_lines_range.clone(),
+				line,
+				line_span.clone(),
+				segment.clone(),
+			),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
@@ -0,0 +1,308 @@
+use super::gradient::{ColorStop, Ramp, Spread};
+use crate::{geometry::Point, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use std::{marker::PhantomData, ops::Range};
+
+/// The algorithm behind a [`Noise`] instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoiseKind {
+	/// Smoothly interpolated random values on a square grid. Blocky, but cheap.
+	Value,
+	/// Ken Perlin's gradient noise on a square grid.
+	Perlin,
+	/// Gradient noise on a triangular grid, with fewer directional artifacts than [`NoiseKind::Perlin`].
+	Simplex,
+}
+
+/// Dynamically masked coherent noise, mapped to colours through gradient stops.
+///
+/// Noise values are mapped to `0.0..=1.0` (and usually stay well within it) before colours are looked up.
+/// The same seed always produces the same image, on every platform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Noise<
+	P: PixelFormat,
+	L: Fn(Option<Range<isize>>) -> Range<isize>,
+	S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+> {
+	lines: L,
+	segments: S,
+	kind: NoiseKind,
+	seed: u64,
+	scale: f32,
+	octaves: u32,
+	lacunarity: f32,
+	gain: f32,
+	ramp: Ramp,
+	_phantom: PhantomData<P>,
+}
+
+impl<
+		P: PixelFormat,
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Noise<P, L, S>
+{
+	/// Creates a new [`Noise`] instance with features about `scale` pixels in size.
+	///
+	/// `stops` don't have to be sorted.
+	///
+	/// # Panics
+	///
+	/// Iff any stop offset is NaN.
+	pub fn new(
+		lines: L,
+		segments: S,
+		kind: NoiseKind,
+		seed: u64,
+		scale: f32,
+		stops: impl IntoIterator<Item = ColorStop>,
+	) -> Self {
+		Self {
+			lines,
+			segments,
+			kind,
+			seed,
+			scale,
+			octaves: 1,
+			lacunarity: 2.,
+			gain: 0.5,
+			ramp: Ramp::new(stops, Spread::Pad),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Turns this into fractal Brownian motion, summing `octaves` layers of noise.
+	///
+	/// Each layer has `lacunarity` times the frequency and `gain` times the amplitude of the previous one.
+	/// `2.0` and `0.5` are typical.
+	#[must_use]
+	pub fn fbm(self, octaves: u32, lacunarity: f32, gain: f32) -> Self {
+		Self {
+			octaves,
+			lacunarity,
+			gain,
+			..self
+		}
+	}
+
+	/// Enables ordered dithering, which avoids visible banding in shallow colour ramps.
+	#[must_use]
+	pub fn dithered(self) -> Self {
+		Self {
+			ramp: self.ramp.dithered(),
+			..self
+		}
+	}
+
+	fn t(&self, point: Point) -> f32 {
+		let mut point = point * (1. / self.scale);
+		let mut amplitude = 1.;
+		let mut sum = 0.;
+		let mut total = 0.;
+		let mut seed = self.seed;
+		for _ in 0..self.octaves {
+			let value = match self.kind {
+				NoiseKind::Value => value(seed, point),
+				NoiseKind::Perlin => perlin(seed, point),
+				NoiseKind::Simplex => simplex(seed, point),
+			};
+			sum += value * amplitude;
+			total += amplitude;
+			amplitude *= self.gain;
+			point = point * self.lacunarity;
+			seed = mix(seed.wrapping_add(1));
+		}
+		if total > 0. {
+			0.5 + 0.5 * sum / total
+		} else {
+			0.5
+		}
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Sprite<RgbaNoPadding<8>> for Noise<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_under(line, segment, data, |point| self.t(point))
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Effect<RgbaNoPadding<8>> for Noise<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_over(line, segment, data, |point| self.t(point))
+	}
+}
+
+/// Scrambles `h` with the finalizer of the `SplitMix64` generator.
+fn mix(mut h: u64) -> u64 {
+	h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	h ^ (h >> 31)
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn hash(seed: u64, x: i64, y: i64) -> u64 {
+	mix(mix(seed ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+		^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn lattice(x: f32) -> (i64, f32) {
+	let floor = x.floor();
+	(floor as i64, x - floor)
+}
+
+/// Quintic smoothstep, with zero first and second derivatives at both ends.
+fn fade(t: f32) -> f32 {
+	t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+	a + (b - a) * t
+}
+
+/// Gets the dot product of `offset` with one of eight unit gradients picked by `hash`.
+fn gradient(hash: u64, offset: Point) -> f32 {
+	const DIAGONAL: f32 = std::f32::consts::FRAC_1_SQRT_2;
+	let (x, y) = match hash & 7 {
+		0 => (1., 0.),
+		1 => (-1., 0.),
+		2 => (0., 1.),
+		3 => (0., -1.),
+		4 => (DIAGONAL, DIAGONAL),
+		5 => (-DIAGONAL, DIAGONAL),
+		6 => (DIAGONAL, -DIAGONAL),
+		_ => (-DIAGONAL, -DIAGONAL),
+	};
+	offset.dot(Point::new(x, y))
+}
+
+/// Interpolates `corner` values of the grid cell around `point`, in `-1.0..=1.0` if they are.
+fn grid(point: Point, corner: impl Fn(i64, i64, Point) -> f32) -> f32 {
+	let (x, fx) = lattice(point.x);
+	let (y, fy) = lattice(point.y);
+	let top = lerp(
+		corner(x, y, Point::new(fx, fy)),
+		corner(x + 1, y, Point::new(fx - 1., fy)),
+		fade(fx),
+	);
+	let bottom = lerp(
+		corner(x, y + 1, Point::new(fx, fy - 1.)),
+		corner(x + 1, y + 1, Point::new(fx - 1., fy - 1.)),
+		fade(fx),
+	);
+	lerp(top, bottom, fade(fy))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn value(seed: u64, point: Point) -> f32 {
+	grid(point, |x, y, _| {
+		(hash(seed, x, y) >> 40) as f32 / (1_u64 << 23) as f32 - 1.
+	})
+}
+
+fn perlin(seed: u64, point: Point) -> f32 {
+	// Unit gradients reach at most half the cell diagonal.
+	grid(point, |x, y, offset| gradient(hash(seed, x, y), offset)) * std::f32::consts::SQRT_2
+}
+
+fn simplex(seed: u64, point: Point) -> f32 {
+	// Skews the triangular grid onto a square one and back.
+	let skew = (3_f32.sqrt() - 1.) / 2.;
+	let unskew = (3. - 3_f32.sqrt()) / 6.;
+
+	let (i, _) = lattice(point.x + (point.x + point.y) * skew);
+	let (j, _) = lattice(point.y + (point.x + point.y) * skew);
+	#[allow(clippy::cast_precision_loss)]
+	let origin = {
+		let (i, j) = (i as f32, j as f32);
+		Point::new(i, j) - Point::new(1., 1.) * ((i + j) * unskew)
+	};
+	let offset = point - origin;
+	let (middle_i, middle_j, middle) = if offset.x > offset.y {
+		(1, 0, Point::new(1., 0.))
+	} else {
+		(0, 1, Point::new(0., 1.))
+	};
+
+	let corners = [
+		(i, j, offset),
+		(
+			i + middle_i,
+			j + middle_j,
+			offset - middle + Point::new(unskew, unskew),
+		),
+		(
+			i + 1,
+			j + 1,
+			offset - Point::new(1., 1.) + Point::new(unskew, unskew) * 2.,
+		),
+	];
+	let sum: f32 = corners
+		.iter()
+		.map(|(x, y, offset)| {
+			let falloff = 0.5 - offset.dot(*offset);
+			if falloff > 0. {
+				falloff.powi(4) * gradient(hash(seed, *x, *y), *offset)
+			} else {
+				0.
+			}
+		})
+		.sum();
+	// Scales the extrema to about ±1.
+	sum * 99.
+}
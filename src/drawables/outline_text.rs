@@ -0,0 +1,216 @@
+use super::glyphs::{self, PlacedGlyph};
+use crate::{
+	blend,
+	geometry::{ceil, floor, Point, Transform},
+	pixel_formats::RgbaNoPadding,
+	raster::{render_coverage, Polygons},
+	text::{BitmapGlyph, GlyphCache, OutlineFont, OUTLINE_TOLERANCE},
+	Effect, Sprite,
+};
+use std::{
+	cmp::{max, min},
+	ops::Range,
+	sync::Arc,
+};
+
+/// Text set in an [`OutlineFont`] at any size, rasterized with anti-aliasing and filled with `paint`.
+///
+/// The first line's top edge is at `y = 0` and lines start at `x = 0`. Glyphs aren't kerned.
+/// `paint` shares the text's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineText<F> {
+	outline: TextOutline,
+	lines: Range<isize>,
+	paint: F,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TextOutline {
+	Polygons(Polygons),
+	Glyphs(Vec<PlacedGlyph<Arc<BitmapGlyph>>>),
+}
+impl TextOutline {
+	fn line_segment(&self, line: isize) -> Range<isize> {
+		match self {
+			TextOutline::Polygons(polygons) => polygons.line_segment(line),
+			TextOutline::Glyphs(placed) => glyphs::line_segment(placed, line),
+		}
+	}
+
+	fn coverage(&self, line: isize, segment: Range<isize>) -> Vec<u8> {
+		match self {
+			TextOutline::Polygons(polygons) => {
+				polygons.coverage(line, segment, |winding| winding != 0)
+			}
+			TextOutline::Glyphs(placed) => glyphs::coverage(placed, line, segment),
+		}
+	}
+}
+
+impl<F> OutlineText<F> {
+	/// Creates a new [`OutlineText`] instance at `size` pixels per em, with `'\n'` starting a new line.
+	///
+	/// Glyphs are positioned with subpixel precision and rasterized as a whole on each line.
+	pub fn new(font: &OutlineFont<'_>, size: f32, text: &str, paint: F) -> Self {
+		Self::set(font, size, text, None, paint)
+	}
+
+	/// Creates a new [`OutlineText`] instance like [`OutlineText::new`], but with glyphs from `cache`.
+	///
+	/// Glyphs are positioned on whole pixels, which is faster to render but less precise.
+	pub fn cached(
+		cache: &GlyphCache,
+		font: &OutlineFont<'_>,
+		size: f32,
+		text: &str,
+		paint: F,
+	) -> Self {
+		Self::set(font, size, text, Some(cache), paint)
+	}
+
+	fn set(
+		font: &OutlineFont<'_>,
+		size: f32,
+		text: &str,
+		cache: Option<&GlyphCache>,
+		paint: F,
+	) -> Self {
+		let scale = size / f32::from(font.units_per_em());
+		let mut glyphs = Vec::new();
+		let mut y = 0.;
+		for line in text.split('\n') {
+			let baseline = y + font.ascent(size);
+			let mut x = 0.;
+			for character in line.trim_end_matches('\r').chars() {
+				let glyph = font.glyph_id(character);
+				glyphs.push((glyph, Point::new(x, baseline)));
+				x += f32::from(font.glyph_advance(glyph)) * scale;
+			}
+			y += font.line_height(size);
+		}
+		Self::from_glyphs(font, size, glyphs, y, cache, paint)
+	}
+
+	/// Creates a new [`OutlineText`] instance from glyphs with their baseline origins, spanning at least `height` pixels from `y = 0`.
+	pub(super) fn from_glyphs(
+		font: &OutlineFont<'_>,
+		size: f32,
+		glyphs: impl IntoIterator<Item = (u16, Point)>,
+		height: f32,
+		cache: Option<&GlyphCache>,
+		paint: F,
+	) -> Self {
+		let (outline, glyph_lines) = if let Some(cache) = cache {
+			let placed: Vec<_> = glyphs
+				.into_iter()
+				.map(|(glyph, origin)| PlacedGlyph {
+					x: floor(origin.x + 0.5),
+					y: floor(origin.y + 0.5),
+					glyph: cache.glyph(font, size, glyph),
+				})
+				.collect();
+			let glyph_lines = glyphs::lines(&placed, 0..0);
+			(TextOutline::Glyphs(placed), glyph_lines)
+		} else {
+			let mut polygons = Polygons::new();
+			for (glyph, origin) in glyphs {
+				let path = font
+					.glyph_path(glyph, size)
+					.transform(&Transform::translate(origin));
+				for (points, _closed) in path.flatten(OUTLINE_TOLERANCE) {
+					polygons.add_polygon(&points);
+				}
+			}
+			let glyph_lines = polygons.lines();
+			(TextOutline::Polygons(polygons), glyph_lines)
+		};
+
+		let lines = if glyph_lines.is_empty() {
+			0..ceil(height)
+		} else {
+			min(0, glyph_lines.start)..max(ceil(height), glyph_lines.end)
+		};
+
+		Self {
+			outline,
+			lines,
+			paint,
+		}
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for OutlineText<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines.clone()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		self.outline.line_segment(line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.paint,
+			&self.outline.coverage(line, segment.clone()),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for OutlineText<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines.clone()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		self.outline.line_segment(line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.paint,
+			&self.outline.coverage(line, segment.clone()),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
@@ -0,0 +1,164 @@
+use super::gradient::{ColorStop, Ramp, Spread};
+use crate::{geometry::Point, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use std::{marker::PhantomData, ops::Range};
+
+/// A dynamically masked radial gradient with a focal point.
+///
+/// Offset `0.0` is at `focus` and offset `1.0` on the circle around `center` with `radius`.
+/// Points not covered by the gradient (which can happen iff `focus` lies outside that circle) are transparent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient<
+	P: PixelFormat,
+	L: Fn(Option<Range<isize>>) -> Range<isize>,
+	S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+> {
+	lines: L,
+	segments: S,
+	center: Point,
+	radius: f32,
+	focus: Point,
+	ramp: Ramp,
+	_phantom: PhantomData<P>,
+}
+
+impl<
+		P: PixelFormat,
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> RadialGradient<P, L, S>
+{
+	/// Creates a new [`RadialGradient`] instance.
+	///
+	/// `stops` don't have to be sorted. Use `center` as `focus` for a plain circular gradient.
+	///
+	/// # Panics
+	///
+	/// Iff any stop offset is NaN.
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		lines: L,
+		segments: S,
+		center: Point,
+		radius: f32,
+		focus: Point,
+		stops: impl IntoIterator<Item = ColorStop>,
+		spread: Spread,
+	) -> Self {
+		Self {
+			lines,
+			segments,
+			center,
+			radius,
+			focus,
+			ramp: Ramp::new(stops, spread),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Enables ordered dithering, which avoids visible banding in shallow gradients.
+	#[must_use]
+	pub fn dithered(self) -> Self {
+		Self {
+			ramp: self.ramp.dithered(),
+			..self
+		}
+	}
+
+	/// Finds the largest non-negative `t` for which `point` lies on the circle
+	/// with radius `t * radius` around `focus + t * (center - focus)`.
+	fn t(&self, point: Point) -> f32 {
+		let axis = self.center - self.focus;
+		let point = point - self.focus;
+
+		// a·t² - 2b·t + c = 0
+		let a = axis.dot(axis) - self.radius * self.radius;
+		let b = point.dot(axis);
+		let c = point.dot(point);
+
+		if a == 0. {
+			let t = c / (2. * b);
+			return if t >= 0. { t } else { f32::NAN };
+		}
+
+		let discriminant = b * b - a * c;
+		if discriminant < 0. {
+			return f32::NAN;
+		}
+		let root = discriminant.sqrt();
+		let (t_1, t_2) = ((b + root) / a, (b - root) / a);
+		let t = t_1.max(t_2);
+		if t >= 0. {
+			t
+		} else {
+			f32::NAN
+		}
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Sprite<RgbaNoPadding<8>> for RadialGradient<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_under(line, segment, data, |point| self.t(point))
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	> Effect<RgbaNoPadding<8>> for RadialGradient<RgbaNoPadding<8>, L, S>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.ramp
+			.render_over(line, segment, data, |point| self.t(point))
+	}
+}
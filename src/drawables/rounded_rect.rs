@@ -0,0 +1,136 @@
+use super::shape::{self, ConvexOutline};
+use crate::{blend, geometry::Point, pixel_formats::RgbaNoPadding, Effect, Sprite};
+use std::ops::Range;
+
+/// An axis-aligned rectangle with circular corners, filled with `paint` and with anti-aliased edges.
+///
+/// `paint` shares this shape's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedRect<F> {
+	outline: RoundedRectOutline,
+	paint: F,
+}
+impl<F> RoundedRect<F> {
+	/// Creates a new [`RoundedRect`] instance.
+	///
+	/// `corner_radius` is limited to half the shorter side length.
+	pub fn new(top_left: Point, bottom_right: Point, corner_radius: f32, paint: F) -> Self {
+		let corner_radius = corner_radius
+			.min((bottom_right.x - top_left.x) / 2.)
+			.min((bottom_right.y - top_left.y) / 2.)
+			.max(0.);
+		Self {
+			outline: RoundedRectOutline {
+				top_left,
+				bottom_right,
+				corner_radius,
+			},
+			paint,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RoundedRectOutline {
+	top_left: Point,
+	bottom_right: Point,
+	corner_radius: f32,
+}
+impl ConvexOutline for RoundedRectOutline {
+	fn top_bottom(&self) -> (f32, f32) {
+		(self.top_left.y, self.bottom_right.y)
+	}
+
+	fn widest(&self) -> f32 {
+		self.top_left.y + (self.bottom_right.y - self.top_left.y) / 2.
+	}
+
+	fn span(&self, y: f32) -> Option<(f32, f32)> {
+		if y < self.top_left.y || y > self.bottom_right.y {
+			return None;
+		}
+
+		let radius = self.corner_radius;
+		let corner_dy = (self.top_left.y + radius - y)
+			.max(y - (self.bottom_right.y - radius))
+			.max(0.);
+		let inset = radius - (radius * radius - corner_dy * corner_dy).max(0.).sqrt();
+		Some((self.top_left.x + inset, self.bottom_right.x - inset))
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for RoundedRect<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		shape::lines(&self.outline)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		shape::line_segment(&self.outline, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		shape::render(
+			&self.outline,
+			&self.paint,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for RoundedRect<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		shape::lines(&self.outline)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		shape::line_segment(&self.outline, line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		shape::render(
+			&self.outline,
+			&self.paint,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
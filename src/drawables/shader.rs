@@ -0,0 +1,226 @@
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use std::{marker::PhantomData, ops::Range};
+
+/// A dynamically masked sprite coloured pixel by pixel by a closure.
+///
+/// `shade` is called with `x` and `y` of each rendered pixel and returns its premultiplied colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelShader<
+	P: PixelFormat,
+	L: Fn(Option<Range<isize>>) -> Range<isize>,
+	S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	C,
+> {
+	lines: L,
+	segments: S,
+	shade: C,
+	_phantom: PhantomData<P>,
+}
+
+impl<
+		P: PixelFormat,
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C,
+	> PixelShader<P, L, S, C>
+{
+	/// Creates a new [`PixelShader`] instance.
+	pub fn new(lines: L, segments: S, shade: C) -> Self {
+		Self {
+			lines,
+			segments,
+			shade,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C: Fn(isize, isize) -> [u8; 4],
+	> Sprite<RgbaNoPadding<8>> for PixelShader<RgbaNoPadding<8>, L, S, C>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		blend::under_rgba8_iter(data, segment.map(|x| (self.shade)(x, line)))
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C: Fn(isize, isize) -> [u8; 4],
+	> Effect<RgbaNoPadding<8>> for PixelShader<RgbaNoPadding<8>, L, S, C>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		blend::over_rgba8_iter(data, segment.map(|x| (self.shade)(x, line)))
+	}
+}
+
+/// A dynamically masked sprite coloured segment by segment by a closure.
+///
+/// `shade` is called with `y`, the `x` range of the rendered segment and a transparent buffer for it,
+/// which it fills with premultiplied pixels in the same format as the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SegmentShader<
+	P: PixelFormat,
+	L: Fn(Option<Range<isize>>) -> Range<isize>,
+	S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+	C,
+> {
+	lines: L,
+	segments: S,
+	shade: C,
+	_phantom: PhantomData<P>,
+}
+
+impl<
+		P: PixelFormat,
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C,
+	> SegmentShader<P, L, S, C>
+{
+	/// Creates a new [`SegmentShader`] instance.
+	pub fn new(lines: L, segments: S, shade: C) -> Self {
+		Self {
+			lines,
+			segments,
+			shade,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C: Fn(isize, Range<isize>, &mut [u8]),
+	> SegmentShader<RgbaNoPadding<8>, L, S, C>
+{
+	fn render_with(
+		&self,
+		line: isize,
+		segment: Range<isize>,
+		data: &mut [u8],
+		blend: fn(&mut [u8], &[u8]),
+	) {
+		let mut scratch = vec![0; data.len()];
+		(self.shade)(line, segment, &mut scratch);
+		blend(data, &scratch)
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C: Fn(isize, Range<isize>, &mut [u8]),
+	> Sprite<RgbaNoPadding<8>> for SegmentShader<RgbaNoPadding<8>, L, S, C>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.render_with(line, segment, data, blend::under_rgba8_pixels)
+	}
+}
+
+impl<
+		L: Fn(Option<Range<isize>>) -> Range<isize>,
+		S: Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+		C: Fn(isize, Range<isize>, &mut [u8]),
+	> Effect<RgbaNoPadding<8>> for SegmentShader<RgbaNoPadding<8>, L, S, C>
+{
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		(self.lines)(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		(self.segments)(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		self.render_with(line, segment, data, blend::over_rgba8_pixels)
+	}
+}
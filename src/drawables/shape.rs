@@ -0,0 +1,79 @@
+use crate::{
+	geometry::{ceil, floor, pixel_center},
+	pixel_formats::RgbaNoPadding,
+	raster::{render_coverage, sample_ys, Coverage, SUBSAMPLE_WEIGHT},
+	Sprite,
+};
+use std::ops::Range;
+
+/// The outline of a convex shape, which has at most one span on each horizontal.
+pub(super) trait ConvexOutline {
+	/// Gets the topmost and bottommost `y` coordinate of the shape.
+	fn top_bottom(&self) -> (f32, f32);
+
+	/// Gets a `y` coordinate at which the shape is widest.
+	fn widest(&self) -> f32;
+
+	/// Gets the horizontal span of the shape at `y`, if any.
+	fn span(&self, y: f32) -> Option<(f32, f32)>;
+}
+
+pub(super) fn lines(outline: &impl ConvexOutline) -> Range<isize> {
+	let (top, bottom) = outline.top_bottom();
+	floor(top)..ceil(bottom)
+}
+
+/// Gets the exact pixel range touched by `outline` on `line`.
+pub(super) fn line_segment(outline: &impl ConvexOutline, line: isize) -> Range<isize> {
+	let (top, bottom) = outline.top_bottom();
+	let line_top = pixel_center(line) - 0.5;
+	let (y_0, y_1) = (line_top.max(top), (line_top + 1.).min(bottom));
+	if y_0 > y_1 {
+		return 0..0;
+	}
+
+	// The horizontal extent of a convex shape is largest at either end of the line or at its widest point.
+	let (left, right) = [y_0, y_1, outline.widest().clamp(y_0, y_1)]
+		.iter()
+		.filter_map(|y| outline.span(*y))
+		.fold(
+			(f32::INFINITY, f32::NEG_INFINITY),
+			|(left, right), (x_0, x_1)| (left.min(x_0), right.max(x_1)),
+		);
+	if left < right {
+		floor(left)..ceil(right)
+	} else {
+		0..0
+	}
+}
+
+/// Renders `paint` clipped to `outline`, with anti-aliased edges.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn render<F: Sprite<RgbaNoPadding<8>>>(
+	outline: &impl ConvexOutline,
+	paint: &F,
+	all_lines_range: Option<Range<isize>>,
+	line: isize,
+	line_span: Range<isize>,
+	segment: Range<isize>,
+	data: &mut [u8],
+	blend: fn(&mut [u8], &[u8]),
+) {
+	let mut coverage = Coverage::new(segment.clone());
+	for y in sample_ys(line) {
+		if let Some((x_0, x_1)) = outline.span(y) {
+			coverage.add_span(x_0, x_1, SUBSAMPLE_WEIGHT);
+		}
+	}
+
+	render_coverage(
+		paint,
+		&coverage.finish(),
+		all_lines_range,
+		line,
+		line_span,
+		segment,
+		data,
+		blend,
+	)
+}
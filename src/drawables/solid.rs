@@ -0,0 +1,89 @@
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use std::{marker::PhantomData, ops::Range};
+
+/// An unbounded flat colour, mainly useful as paint for shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Solid<P: PixelFormat, C> {
+	color: C,
+	_phantom: PhantomData<P>,
+}
+impl<P: PixelFormat, C> Solid<P, C> {
+	/// Creates a new [`Solid`] instance.
+	pub fn new(color: C) -> Self {
+		Self {
+			color,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl Sprite<RgbaNoPadding<8>> for Solid<RgbaNoPadding<8>, [u8; 4]> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		all_lines_range.unwrap_or(isize::MIN..isize::MAX)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		line_span
+	}
+
+	fn opaque_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		if self.color[3] == u8::MAX {
+			line_span
+		} else {
+			0..0
+		}
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		_line_span: Range<isize>,
+		_segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		blend::under_rgba8_color(data, self.color)
+	}
+}
+
+impl Effect<RgbaNoPadding<8>> for Solid<RgbaNoPadding<8>, [u8; 4]> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		all_lines_range.unwrap_or(isize::MIN..isize::MAX)
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		line_span
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		_line_span: Range<isize>,
+		_segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		blend::over_rgba8_color(data, self.color)
+	}
+}
@@ -0,0 +1,612 @@
+use crate::{
+	blend,
+	geometry::{Path, Point},
+	pixel_formats::RgbaNoPadding,
+	raster::{render_coverage, Polygons},
+	Effect, Intersect, Position, Sprite,
+};
+use std::{
+	cmp::{max, min},
+	convert::TryInto,
+	f32::consts::PI,
+	ops::Range,
+};
+
+/// The shape at the open ends of a stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+	/// Ends exactly at the end point.
+	Butt,
+	/// Extends by a half circle around the end point.
+	Round,
+	/// Extends by half the stroke width past the end point.
+	Square,
+}
+
+/// The shape at the corners of a stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+	/// Extends the outer edges until they meet, unless that exceeds [`StrokeStyle::miter_limit`].
+	Miter,
+	/// Rounds the outer corner around the vertex.
+	Round,
+	/// Cuts the outer corner off straight.
+	Bevel,
+}
+
+/// Geometry settings of a [`Stroke`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+	/// Stroke width, in pixels.
+	pub width: f32,
+	/// Shape of open ends.
+	pub cap: LineCap,
+	/// Shape of corners.
+	pub join: LineJoin,
+	/// Maximum ratio of miter length to half the stroke width before [`LineJoin::Miter`] falls back to [`LineJoin::Bevel`].
+	pub miter_limit: f32,
+	/// Alternating lengths of dashes and gaps, in pixels. Empty for a solid stroke.
+	///
+	/// As in SVG, an odd number of lengths is repeated once, and invalid patterns (with negative lengths or no positive one) are ignored.
+	/// Zero-length dashes become dots if [`StrokeStyle::cap`] isn't [`LineCap::Butt`].
+	/// Polylines that the pattern would split into more than 65536 dashes and gaps are skipped, so they aren't stroked at all.
+	pub dash_array: Vec<f32>,
+	/// Distance into the dash pattern at the start of each polyline or subpath, in pixels.
+	pub dash_offset: f32,
+}
+impl Default for StrokeStyle {
+	fn default() -> Self {
+		Self {
+			width: 1.,
+			cap: LineCap::Butt,
+			join: LineJoin::Miter,
+			miter_limit: 4.,
+			dash_array: Vec::new(),
+			dash_offset: 0.,
+		}
+	}
+}
+
+/// A stroked polyline filled with `paint`, either anti-aliased or as one-pixel Bresenham hairline.
+///
+/// `paint` shares this shape's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke<F> {
+	outline: StrokeOutline,
+	paint: F,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum StrokeOutline {
+	Polygons(Polygons),
+	Hairlines(Vec<Vec<Position>>),
+}
+
+impl<F> Stroke<F> {
+	/// Creates a new [`Stroke`] instance along the open polyline through `points`.
+	pub fn new(points: impl IntoIterator<Item = Point>, style: &StrokeStyle, paint: F) -> Self {
+		Self::from_polylines(
+			Some((points.into_iter().collect::<Vec<_>>(), false)),
+			style,
+			paint,
+		)
+	}
+
+	/// Creates a new [`Stroke`] instance along the closed polygon through `points`.
+	pub fn closed(points: impl IntoIterator<Item = Point>, style: &StrokeStyle, paint: F) -> Self {
+		Self::from_polylines(
+			Some((points.into_iter().collect::<Vec<_>>(), true)),
+			style,
+			paint,
+		)
+	}
+
+	/// Creates a new [`Stroke`] instance along all subpaths of `path`.
+	///
+	/// Curves are flattened to polylines that deviate from them by at most `tolerance` pixels.
+	pub fn path(path: &Path, tolerance: f32, style: &StrokeStyle, paint: F) -> Self {
+		Self::from_polylines(path.flatten(tolerance), style, paint)
+	}
+
+	/// Creates a new one-pixel [`Stroke`] instance through the pixels at `points`, without anti-aliasing.
+	///
+	/// This is much faster than [`Stroke::new`], as it only calculates the Bresenham line pixels in each line.
+	pub fn hairline(points: impl IntoIterator<Item = Position>, paint: F) -> Self {
+		Self {
+			outline: StrokeOutline::Hairlines(vec![points.into_iter().collect()]),
+			paint,
+		}
+	}
+
+	/// Creates a new [`Stroke`] instance along several polylines, each of which is closed iff flagged so.
+	pub(crate) fn from_polylines(
+		polylines: impl IntoIterator<Item = (Vec<Point>, bool)>,
+		style: &StrokeStyle,
+		paint: F,
+	) -> Self {
+		let mut polygons = Polygons::new();
+		let pattern = dash_pattern(&style.dash_array);
+		for (points, closed) in polylines {
+			match &pattern {
+				Some(pattern) => {
+					if dash_count(&points, closed, pattern) <= MAX_DASHES {
+						for dash in dash_polyline(&points, closed, pattern, style.dash_offset) {
+							stroke_polyline(&mut polygons, &dash, false, style);
+						}
+					}
+				}
+				None => stroke_polyline(&mut polygons, &points, closed, style),
+			}
+		}
+		Self {
+			outline: StrokeOutline::Polygons(polygons),
+			paint,
+		}
+	}
+
+	fn lines(&self) -> Range<isize> {
+		match &self.outline {
+			StrokeOutline::Polygons(polygons) => polygons.lines(),
+			StrokeOutline::Hairlines(hairlines) => hairlines
+				.iter()
+				.flatten()
+				.fold(None, |lines: Option<Range<isize>>, point| {
+					Some(match lines {
+						None => point.y..point.y + 1,
+						Some(lines) => min(lines.start, point.y)..max(lines.end, point.y + 1),
+					})
+				})
+				.unwrap_or(0..0),
+		}
+	}
+
+	fn line_segment(&self, line: isize) -> Range<isize> {
+		match &self.outline {
+			StrokeOutline::Polygons(polygons) => polygons.line_segment(line),
+			StrokeOutline::Hairlines(hairlines) => hairline_runs(hairlines, line)
+				.reduce(|a, b| min(a.start, b.start)..max(a.end, b.end))
+				.unwrap_or(0..0),
+		}
+	}
+
+	fn coverage(&self, line: isize, segment: Range<isize>) -> Vec<u8> {
+		match &self.outline {
+			StrokeOutline::Polygons(polygons) => {
+				polygons.coverage(line, segment, |winding| winding != 0)
+			}
+			StrokeOutline::Hairlines(hairlines) => {
+				let mut coverage = vec![0; segment.len()];
+				for run in hairline_runs(hairlines, line) {
+					if let Some(run) = run.intersect(segment.clone()) {
+						let start: usize =
+							(run.start - segment.start).try_into().expect("infallible");
+						for coverage in &mut coverage[start..start + run.len()] {
+							*coverage = u8::MAX;
+						}
+					}
+				}
+				coverage
+			}
+		}
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for Stroke<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		self.line_segment(line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.paint,
+			&self.coverage(line, segment.clone()),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::under_rgba8_pixels,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for Stroke<F> {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.lines()
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		self.line_segment(line)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		assert_eq!(offset_bits, 0);
+
+		render_coverage(
+			&self.paint,
+			&self.coverage(line, segment.clone()),
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			data,
+			blend::over_rgba8_pixels,
+		)
+	}
+}
+
+/// Converts a polyline into positively oriented polygons, so that their union is filled with the non-zero rule.
+fn stroke_polyline(polygons: &mut Polygons, points: &[Point], closed: bool, style: &StrokeStyle) {
+	let half_width = style.width / 2.;
+	if half_width <= 0. || half_width.is_nan() {
+		return;
+	}
+
+	let mut points = points.to_vec();
+	points.dedup();
+	if closed && points.len() > 1 && points.first() == points.last() {
+		points.pop();
+	}
+
+	let count = points.len();
+	if count == 1 {
+		if !closed {
+			let point = points[0];
+			match style.cap {
+				LineCap::Butt => (),
+				LineCap::Round => add_positive_polygon(polygons, circle(point, half_width)),
+				LineCap::Square => add_positive_polygon(
+					polygons,
+					vec![
+						point + Point::new(-half_width, -half_width),
+						point + Point::new(half_width, -half_width),
+						point + Point::new(half_width, half_width),
+						point + Point::new(-half_width, half_width),
+					],
+				),
+			}
+		}
+		return;
+	} else if count == 0 {
+		return;
+	}
+
+	let segment_count = if closed { count } else { count - 1 };
+	for i in 0..segment_count {
+		let (from, to) = (points[i], points[(i + 1) % count]);
+		let normal = unit_normal(from, to) * half_width;
+		add_positive_polygon(
+			polygons,
+			vec![from + normal, to + normal, to - normal, from - normal],
+		);
+	}
+
+	let joins = if closed { 0..count } else { 1..count - 1 };
+	for i in joins {
+		join(
+			polygons,
+			points[(i + count - 1) % count],
+			points[i],
+			points[(i + 1) % count],
+			style,
+		);
+	}
+
+	if !closed {
+		cap(polygons, points[0], points[1], style);
+		cap(polygons, points[count - 1], points[count - 2], style);
+	}
+}
+
+/// Validates `dash_array` and repeats it if its length is odd.
+fn dash_pattern(dash_array: &[f32]) -> Option<Vec<f32>> {
+	if dash_array
+		.iter()
+		.any(|length| !length.is_finite() || *length < 0.)
+		|| !dash_array.iter().any(|length| *length > 0.)
+	{
+		return None;
+	}
+
+	let mut pattern = dash_array.to_vec();
+	if pattern.len() % 2 == 1 {
+		pattern.extend_from_slice(dash_array);
+	}
+	Some(pattern)
+}
+
+/// The most dashes and gaps a single polyline is split into. Denser polylines are skipped.
+///
+/// This also keeps each pattern repetition long enough to advance along a segment despite `f32` rounding.
+const MAX_DASHES: f32 = 65536.;
+
+/// Estimates how many dashes and gaps `pattern` splits a polyline into, which is NaN or infinite for degenerate input.
+fn dash_count(points: &[Point], closed: bool, pattern: &[f32]) -> f32 {
+	let closing = if closed {
+		points.last().zip(points.first())
+	} else {
+		None
+	};
+	let length: f32 = points
+		.iter()
+		.zip(points.iter().skip(1))
+		.chain(closing)
+		.map(|(from, to)| (*to - *from).length())
+		.sum();
+	#[allow(clippy::cast_precision_loss)]
+	let count = length / pattern.iter().sum::<f32>() * pattern.len() as f32;
+	count
+}
+
+/// Splits a polyline into the open polylines of its dashes.
+///
+/// The pattern continues across vertices, so dashes around corners keep their joins.
+/// On closed polylines, dashes running through the start point are merged.
+fn dash_polyline(points: &[Point], closed: bool, pattern: &[f32], offset: f32) -> Vec<Vec<Point>> {
+	let mut index = 0;
+	let mut remaining = pattern[0];
+	let mut offset = offset.rem_euclid(pattern.iter().sum());
+	// Zero-length dashes at the very start are kept, so they can become dots.
+	while remaining < offset || (0. < remaining && remaining <= offset) {
+		offset -= remaining;
+		index = (index + 1) % pattern.len();
+		remaining = pattern[index];
+	}
+	remaining -= offset;
+
+	let mut points = points.to_vec();
+	if closed {
+		points.extend(points.first().copied());
+	}
+	let starts_on = index % 2 == 0;
+
+	let mut dashes = Vec::new();
+	let mut dash = match points.first() {
+		Some(first) if starts_on => vec![*first],
+		_ => Vec::new(),
+	};
+	for (from, to) in points.iter().zip(points.iter().skip(1)) {
+		let length = (*to - *from).length();
+		if length == 0. {
+			continue;
+		}
+
+		let mut distance = 0.;
+		loop {
+			let on = index % 2 == 0;
+			if remaining > length - distance {
+				remaining -= length - distance;
+				if on {
+					dash.push(*to);
+				}
+				break;
+			}
+
+			distance += remaining;
+			let point = *from + (*to - *from) * (distance / length);
+			if on {
+				dash.push(point);
+				dashes.push(std::mem::take(&mut dash));
+			} else {
+				dash = vec![point];
+			}
+			index = (index + 1) % pattern.len();
+			remaining = pattern[index];
+		}
+	}
+
+	if index % 2 == 0 && !dash.is_empty() {
+		if closed && starts_on && !dashes.is_empty() {
+			let first = dashes.remove(0);
+			dash.extend(first.into_iter().skip(1));
+		}
+		dashes.push(dash);
+	}
+	dashes
+}
+
+fn join(polygons: &mut Polygons, previous: Point, vertex: Point, next: Point, style: &StrokeStyle) {
+	let half_width = style.width / 2.;
+	let turn = (vertex - previous).cross(next - vertex);
+	#[allow(clippy::float_cmp)]
+	if turn == 0. {
+		// Collinear segments need no join.
+		return;
+	}
+
+	// The outer side of the corner is opposite the turning direction.
+	let side = if turn > 0. { -1. } else { 1. };
+	let outer_a = unit_normal(previous, vertex) * side;
+	let outer_b = unit_normal(vertex, next) * side;
+	let bevel = vec![
+		vertex,
+		vertex + outer_a * half_width,
+		vertex + outer_b * half_width,
+	];
+
+	match style.join {
+		LineJoin::Bevel => add_positive_polygon(polygons, bevel),
+		LineJoin::Round => add_positive_polygon(polygons, circle(vertex, half_width)),
+		LineJoin::Miter => {
+			let direction = outer_a + outer_b;
+			let cosine = direction.dot(outer_a);
+			if cosine > 0. && direction.length() / cosine <= style.miter_limit {
+				add_positive_polygon(
+					polygons,
+					vec![
+						vertex,
+						vertex + outer_a * half_width,
+						vertex + direction * (half_width / cosine),
+						vertex + outer_b * half_width,
+					],
+				)
+			} else {
+				add_positive_polygon(polygons, bevel)
+			}
+		}
+	}
+}
+
+fn cap(polygons: &mut Polygons, end: Point, inner: Point, style: &StrokeStyle) {
+	let half_width = style.width / 2.;
+	match style.cap {
+		LineCap::Butt => (),
+		LineCap::Round => add_positive_polygon(polygons, circle(end, half_width)),
+		LineCap::Square => {
+			let normal = unit_normal(inner, end) * half_width;
+			let extension = Point::new(normal.y, -normal.x);
+			add_positive_polygon(
+				polygons,
+				vec![
+					end + normal,
+					end + normal + extension,
+					end - normal + extension,
+					end - normal,
+				],
+			)
+		}
+	}
+}
+
+/// Gets the unit vector perpendicular to the direction from `from` to `to`.
+fn unit_normal(from: Point, to: Point) -> Point {
+	let direction = to - from;
+	Point::new(-direction.y, direction.x) * (1. / direction.length())
+}
+
+/// Approximates a circle to within a tenth of a pixel.
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_precision_loss,
+	clippy::cast_sign_loss
+)]
+pub(crate) fn circle(center: Point, radius: f32) -> Vec<Point> {
+	let step = 2. * (1. - 0.1 / radius).max(0.).acos();
+	let count = ((2. * PI / step).ceil() as usize).clamp(8, 1024);
+	(0..count)
+		.map(|i| {
+			let angle = 2. * PI * i as f32 / count as f32;
+			center + Point::new(angle.cos(), angle.sin()) * radius
+		})
+		.collect()
+}
+
+fn add_positive_polygon(polygons: &mut Polygons, mut points: Vec<Point>) {
+	let doubled_area: f32 = points
+		.iter()
+		.zip(points.iter().cycle().skip(1))
+		.map(|(a, b)| a.cross(*b))
+		.sum();
+	if doubled_area < 0. {
+		points.reverse();
+	}
+	polygons.add_polygon(&points)
+}
+
+/// Gets the pixel runs of Bresenham lines through `hairlines` on `line`, which may overlap.
+fn hairline_runs(
+	hairlines: &[Vec<Position>],
+	line: isize,
+) -> impl '_ + Iterator<Item = Range<isize>> {
+	hairlines
+		.iter()
+		.flat_map(|hairline| {
+			hairline
+				.iter()
+				.zip(hairline.iter().skip(1))
+				.chain(if hairline.len() == 1 {
+					Some((&hairline[0], &hairline[0]))
+				} else {
+					None
+				})
+		})
+		.filter_map(move |(from, to)| bresenham_run(*from, *to, line))
+}
+
+/// Gets the pixels of the Bresenham line from `from` to `to` (inclusive) on `line`.
+fn bresenham_run(from: Position, to: Position, line: isize) -> Option<Range<isize>> {
+	let (dx, dy) = (to.x - from.x, to.y - from.y);
+
+	if dx.abs() >= dy.abs() {
+		// x-major: Each line contains a contiguous run.
+		let (from, dx, dy) = if dx < 0 {
+			(to, -dx, -dy)
+		} else {
+			(from, dx, dy)
+		};
+		if dy == 0 {
+			return if line == from.y {
+				Some(from.x..from.x + dx + 1)
+			} else {
+				None
+			};
+		}
+
+		let rise = (line - from.y) * dy.signum();
+		if rise < 0 || rise > dy.abs() {
+			return None;
+		}
+		let first = ceil_div((2 * rise - 1) * dx, 2 * dy.abs()).max(0);
+		let last = (ceil_div((2 * rise + 1) * dx, 2 * dy.abs()) - 1).min(dx);
+		if first <= last {
+			Some(from.x + first..from.x + last + 1)
+		} else {
+			None
+		}
+	} else {
+		// y-major: Each line contains exactly one pixel.
+		let (from, dx, dy) = if dy < 0 {
+			(to, -dx, -dy)
+		} else {
+			(from, dx, dy)
+		};
+		let rise = line - from.y;
+		if rise < 0 || rise > dy {
+			return None;
+		}
+		let x = from.x + dx.signum() * ((2 * rise * dx.abs() + dy) / (2 * dy));
+		Some(x..x + 1)
+	}
+}
+
+/// Divides `numerator` by the positive `denominator`, rounding towards positive infinity.
+fn ceil_div(numerator: isize, denominator: isize) -> isize {
+	let quotient = numerator / denominator;
+	if numerator % denominator > 0 {
+		quotient + 1
+	} else {
+		quotient
+	}
+}
@@ -0,0 +1,278 @@
+use super::OutlineText;
+use crate::{
+	geometry::Point,
+	pixel_formats::RgbaNoPadding,
+	text::{GlyphCache, OutlineFont},
+	Effect, Sprite,
+};
+use std::ops::Range;
+
+/// Horizontal placement of the lines of a [`TextBlock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alignment {
+	/// Lines start at the left edge.
+	Left,
+	/// Lines are centred between the edges.
+	Center,
+	/// Lines end at the right edge.
+	Right,
+	/// Spaces are stretched so that lines touch both edges, except for the last line of each paragraph.
+	Justify,
+}
+
+/// Layout settings of a [`TextBlock`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+	/// Font size, in pixels per em.
+	pub size: f32,
+	/// Width that lines are wrapped to, in pixels. [`f32::INFINITY`] to only break lines at `'\n'`.
+	///
+	/// Words too wide to fit on their own are broken between characters.
+	pub max_width: f32,
+	/// Horizontal placement of lines.
+	///
+	/// Without a finite [`TextStyle::max_width`], lines are aligned to the widest one.
+	pub alignment: Alignment,
+	/// Distance between baselines, as multiple of the font's line height.
+	pub line_spacing: f32,
+}
+impl Default for TextStyle {
+	fn default() -> Self {
+		Self {
+			size: 16.,
+			max_width: f32::INFINITY,
+			alignment: Alignment::Left,
+			line_spacing: 1.,
+		}
+	}
+}
+
+/// A block of text set in an [`OutlineFont`], wrapped into lines of limited width and filled with `paint`.
+///
+/// Text is split into paragraphs at `'\n'` and into words at spaces. Runs of spaces collapse into one.
+/// The block's top edge is at `y = 0` and its left edge at `x = 0`.
+/// `paint` shares the block's coordinate system. Use [`Solid`](`super::Solid`) for a flat colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBlock<F> {
+	text: OutlineText<F>,
+	line_count: usize,
+	width: f32,
+	height: f32,
+}
+
+/// A word as glyphs with their offset from its start, and its width.
+type Word = (Vec<(u16, f32)>, f32);
+
+impl<F> TextBlock<F> {
+	/// Creates a new [`TextBlock`] instance.
+	///
+	/// Glyphs are positioned with subpixel precision and rasterized as a whole on each line.
+	pub fn new(font: &OutlineFont<'_>, style: TextStyle, text: &str, paint: F) -> Self {
+		Self::set(font, style, text, None, paint)
+	}
+
+	/// Creates a new [`TextBlock`] instance like [`TextBlock::new`], but with glyphs from `cache`.
+	///
+	/// Glyphs are positioned on whole pixels, which is faster to render but less precise.
+	pub fn cached(
+		cache: &GlyphCache,
+		font: &OutlineFont<'_>,
+		style: TextStyle,
+		text: &str,
+		paint: F,
+	) -> Self {
+		Self::set(font, style, text, Some(cache), paint)
+	}
+
+	fn set(
+		font: &OutlineFont<'_>,
+		style: TextStyle,
+		text: &str,
+		cache: Option<&GlyphCache>,
+		paint: F,
+	) -> Self {
+		let scale = style.size / f32::from(font.units_per_em());
+		let advance = |glyph| f32::from(font.glyph_advance(glyph)) * scale;
+		let space = advance(font.glyph_id(' '));
+
+		// Wrap greedily into lines of words, each flagged if it ends a paragraph.
+		let mut lines: Vec<(Vec<Word>, bool)> = Vec::new();
+		for paragraph in text.split('\n') {
+			let mut line: Vec<Word> = Vec::new();
+			let mut line_width = 0.;
+			for word in paragraph
+				.trim_end_matches('\r')
+				.split(' ')
+				.filter(|word| !word.is_empty())
+			{
+				let mut glyphs = Vec::new();
+				let mut word_width = 0.;
+				for character in word.chars() {
+					let glyph = font.glyph_id(character);
+					let width = advance(glyph);
+					// Break overlong words at the edge.
+					if word_width + width > style.max_width && !glyphs.is_empty() {
+						let full_word = (std::mem::take(&mut glyphs), word_width);
+						if !line.is_empty() {
+							lines.push((std::mem::take(&mut line), false));
+						}
+						lines.push((vec![full_word], false));
+						word_width = 0.;
+					}
+					glyphs.push((glyph, word_width));
+					word_width += width;
+				}
+
+				if !line.is_empty() && line_width + space + word_width > style.max_width {
+					lines.push((std::mem::take(&mut line), false));
+				}
+				line_width = if line.is_empty() {
+					word_width
+				} else {
+					line_width + space + word_width
+				};
+				line.push((glyphs, word_width));
+			}
+			lines.push((line, true));
+		}
+
+		let widths: Vec<f32> = lines
+			.iter()
+			.map(|(words, _)| natural_width(words, space))
+			.collect();
+		let width = if style.max_width.is_finite() {
+			style.max_width
+		} else {
+			widths.iter().copied().fold(0., f32::max)
+		};
+
+		let line_height = font.line_height(style.size) * style.line_spacing;
+		let mut glyphs = Vec::new();
+		let mut y = 0.;
+		for ((words, paragraph_end), natural) in lines.iter().zip(widths) {
+			let baseline = y + font.ascent(style.size);
+			let (mut x, gap) = match style.alignment {
+				Alignment::Left => (0., space),
+				Alignment::Center => ((width - natural) / 2., space),
+				Alignment::Right => (width - natural, space),
+				Alignment::Justify if *paragraph_end || words.len() < 2 => (0., space),
+				Alignment::Justify => {
+					#[allow(clippy::cast_precision_loss)]
+					let gaps = (words.len() - 1) as f32;
+					(0., space + (width - natural) / gaps)
+				}
+			};
+			for (word, word_width) in words {
+				glyphs.extend(
+					word.iter()
+						.map(|(glyph, offset)| (*glyph, Point::new(x + offset, baseline))),
+				);
+				x += word_width + gap;
+			}
+			y += line_height;
+		}
+
+		Self {
+			text: OutlineText::from_glyphs(font, style.size, glyphs, y, cache, paint),
+			line_count: lines.len(),
+			width,
+			height: y,
+		}
+	}
+
+	/// Gets the number of lines after wrapping.
+	#[must_use]
+	pub fn line_count(&self) -> usize {
+		self.line_count
+	}
+
+	/// Gets the width that lines are aligned within, in pixels.
+	#[must_use]
+	pub fn width(&self) -> f32 {
+		self.width
+	}
+
+	/// Gets the height of all lines together, in pixels.
+	///
+	/// Glyphs may reach slightly beyond it, which [`Sprite::lines`] accounts for.
+	#[must_use]
+	pub fn height(&self) -> f32 {
+		self.height
+	}
+}
+
+fn natural_width(words: &[Word], space: f32) -> f32 {
+	#[allow(clippy::cast_precision_loss)]
+	let spaces = words.len().saturating_sub(1) as f32;
+	words.iter().map(|(_, width)| width).sum::<f32>() + spaces * space
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Sprite<RgbaNoPadding<8>> for TextBlock<F> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		Sprite::lines(&self.text, all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		Sprite::line_segment(&self.text, all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		Sprite::render(
+			&self.text,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			offset_bits,
+			data,
+		)
+	}
+}
+
+impl<F: Sprite<RgbaNoPadding<8>>> Effect<RgbaNoPadding<8>> for TextBlock<F> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		Effect::lines(&self.text, all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		Effect::line_segment(&self.text, all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		Effect::render(
+			&self.text,
+			all_lines_range,
+			line,
+			line_span,
+			segment,
+			offset_bits,
+			data,
+		)
+	}
+}
@@ -1,6 +1,6 @@
-use crate::{pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
+use crate::{blend, pixel_formats::RgbaNoPadding, Effect, PixelFormat, Sprite};
 use std::{convert::TryInto, iter, marker::PhantomData, num::NonZeroUsize, ops::Range};
-use tap::{Conv, Pipe, TryConv};
+use tap::{Pipe, TryConv};
 
 /// An integer-zoomed bitmap sprite, drawn with saturating addition.
 pub struct ZoomedBitmap<'a, P: PixelFormat> {
@@ -91,7 +91,7 @@ impl Sprite<RgbaNoPadding<8>> for ZoomedBitmap<'_, RgbaNoPadding<8>> {
 		);
 		assert_eq!(segment.len() * PIXEL_BYTES, data.len());
 
-		for (src, dest) in self
+		let src = self
 			.data
 			.chunks_exact(self.width * PIXEL_BYTES)
 			.pipe(|lines| repeat_each(lines, self.vertical_zoom_factor_numerator))
@@ -102,19 +102,8 @@ impl Sprite<RgbaNoPadding<8>> for ZoomedBitmap<'_, RgbaNoPadding<8>> {
 			.step_by(self.horizontal_zoom_factor_denominator.get())
 			.skip(segment.start)
 			.take(segment.len())
-			.zip(data.chunks_exact_mut(PIXEL_BYTES))
-		{
-			let dest_alpha = dest[3];
-
-			for (src, dest) in src.iter().zip(dest) {
-				*dest = (*dest).saturating_add(
-					((*src).conv::<u16>() * (u8::MAX - dest_alpha).conv::<u16>()
-						/ u8::MAX.conv::<u16>())
-					.try_conv::<u8>()
-					.expect("infallible"),
-				);
-			}
-		}
+			.map(|pixel| pixel.try_into().expect("infallible"));
+		blend::under_rgba8_iter(data, src)
 	}
 }
 
@@ -167,7 +156,7 @@ impl Effect<RgbaNoPadding<8>> for ZoomedBitmap<'_, RgbaNoPadding<8>> {
 		);
 		assert_eq!(segment.len() * PIXEL_BYTES, data.len());
 
-		for (src, dest) in self
+		let src = self
 			.data
 			.chunks_exact(self.width * PIXEL_BYTES)
 			.pipe(|lines| repeat_each(lines, self.vertical_zoom_factor_numerator))
@@ -178,22 +167,12 @@ impl Effect<RgbaNoPadding<8>> for ZoomedBitmap<'_, RgbaNoPadding<8>> {
 			.step_by(self.horizontal_zoom_factor_denominator.get())
 			.skip(segment.start)
 			.take(segment.len())
-			.zip(data.chunks_exact_mut(PIXEL_BYTES))
-		{
-			let src_alpha = src[3];
-
-			for (src, dest) in src.iter().zip(dest) {
-				*dest = src.saturating_add(
-					((*dest).conv::<u16>() * (u8::MAX - src_alpha).conv::<u16>()
-						/ u8::MAX.conv::<u16>())
-					.try_conv::<u8>()
-					.expect("infallible"),
-				);
-			}
-		}
+			.map(|pixel| pixel.try_into().expect("infallible"));
+		blend::over_rgba8_iter(data, src)
 	}
 }
 
+#[allow(clippy::manual_repeat_n)]
 fn repeat_each<T: Clone>(items: impl IntoIterator<Item = T>, n: usize) -> impl Iterator<Item = T> {
 	items
 		.into_iter()
@@ -0,0 +1,241 @@
+//! [`Sprite`] and [`Effect`] implementations for smart pointers, [`Option`]s and collections.
+//!
+//! [`None`] renders nothing. Tuples, arrays, slices and [`Vec`]s are stacks of layers.
+
+use crate::{segment_bytes, Effect, Intersect, PixelFormat, Sprite};
+use std::{
+	cmp::{max, min},
+	ops::Range,
+	rc::Rc,
+	sync::Arc,
+};
+
+/// Implements [`Sprite::opaque_segment`] with `$body`, or nothing for [`Effect`]s.
+macro_rules! opaque_segment {
+	(Sprite, |$this:ident, $all_lines_range:ident, $line:ident, $line_span:ident| $body:expr) => {
+		fn opaque_segment(
+			&self,
+			$all_lines_range: Option<Range<isize>>,
+			$line: isize,
+			$line_span: Range<isize>,
+		) -> Range<isize> {
+			let $this = self;
+			$body
+		}
+	};
+	(Effect, $($_:tt)*) => {};
+}
+
+macro_rules! forward_pointer {
+	($trait:ident for $pointer:ident) => {
+		impl<T: ?Sized, P: PixelFormat> $trait<P> for $pointer<T>
+		where
+			T: $trait<P>,
+		{
+			fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+				T::lines(self, all_lines_range)
+			}
+
+			fn line_segment(
+				&self,
+				all_lines_range: Option<Range<isize>>,
+				line: isize,
+				line_span: Range<isize>,
+			) -> Range<isize> {
+				T::line_segment(self, all_lines_range, line, line_span)
+			}
+
+			opaque_segment!($trait, |this, all_lines_range, line, line_span| {
+				T::opaque_segment(this, all_lines_range, line, line_span)
+			});
+
+			fn render(
+				&self,
+				all_lines_range: Option<Range<isize>>,
+				line: isize,
+				line_span: Range<isize>,
+				segment: Range<isize>,
+				offset_bits: usize,
+				data: &mut [u8],
+			) {
+				T::render(
+					self,
+					all_lines_range,
+					line,
+					line_span,
+					segment,
+					offset_bits,
+					data,
+				)
+			}
+		}
+	};
+}
+forward_pointer!(Sprite for Box);
+forward_pointer!(Sprite for Rc);
+forward_pointer!(Sprite for Arc);
+forward_pointer!(Effect for Box);
+forward_pointer!(Effect for Rc);
+forward_pointer!(Effect for Arc);
+
+macro_rules! forward_option {
+	($trait:ident) => {
+		impl<T, P: PixelFormat> $trait<P> for Option<T>
+		where
+			T: $trait<P>,
+		{
+			fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+				match self {
+					Some(inner) => inner.lines(all_lines_range),
+					None => 0..0,
+				}
+			}
+
+			fn line_segment(
+				&self,
+				all_lines_range: Option<Range<isize>>,
+				line: isize,
+				line_span: Range<isize>,
+			) -> Range<isize> {
+				match self {
+					Some(inner) => inner.line_segment(all_lines_range, line, line_span),
+					None => 0..0,
+				}
+			}
+
+			opaque_segment!(
+				$trait,
+				|this, all_lines_range, line, line_span| match this {
+					Some(inner) => inner.opaque_segment(all_lines_range, line, line_span),
+					None => 0..0,
+				}
+			);
+
+			fn render(
+				&self,
+				all_lines_range: Option<Range<isize>>,
+				line: isize,
+				line_span: Range<isize>,
+				segment: Range<isize>,
+				offset_bits: usize,
+				data: &mut [u8],
+			) {
+				if let Some(inner) = self {
+					inner.render(all_lines_range, line, line_span, segment, offset_bits, data)
+				}
+			}
+		}
+	};
+}
+forward_option!(Sprite);
+forward_option!(Effect);
+
+/// Implements `$trait` for a stack of layers, which are rendered in order like a list passed to [`render_segment`](`crate::render_segment`).
+///
+/// That means the first layer is in front for [`Sprite`]s and at the back for [`Effect`]s.
+macro_rules! stack {
+	($trait:ident, [$($generics:tt)*] $type:ty, |$this:ident| $layers:expr) => {
+		impl<$($generics)*> $trait<P> for $type {
+			fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+				let $this = self;
+				union($layers.map(|layer: &dyn $trait<P>| layer.lines(all_lines_range.clone())))
+			}
+
+			fn line_segment(
+				&self,
+				all_lines_range: Option<Range<isize>>,
+				line: isize,
+				line_span: Range<isize>,
+			) -> Range<isize> {
+				let $this = self;
+				union(
+					$layers
+						.filter(|layer: &&dyn $trait<P>| {
+							layer.lines(all_lines_range.clone()).contains(&line)
+						})
+						.map(|layer| {
+							layer.line_segment(all_lines_range.clone(), line, line_span.clone())
+						}),
+				)
+			}
+
+			fn render(
+				&self,
+				all_lines_range: Option<Range<isize>>,
+				line: isize,
+				line_span: Range<isize>,
+				segment: Range<isize>,
+				offset_bits: usize,
+				data: &mut [u8],
+			) {
+				let $this = self;
+				for layer in $layers {
+					let layer: &dyn $trait<P> = layer;
+					if !layer.lines(all_lines_range.clone()).contains(&line) {
+						continue;
+					}
+					if let Some(layer_segment) = layer
+						.line_segment(all_lines_range.clone(), line, line_span.clone())
+						.intersect(segment.clone())
+					{
+						let (bytes, layer_offset_bits) = segment_bytes::<P>(&segment, &layer_segment, offset_bits);
+						layer.render(
+							all_lines_range.clone(),
+							line,
+							line_span.clone(),
+							layer_segment,
+							layer_offset_bits,
+							&mut data[bytes],
+						)
+					}
+				}
+			}
+		}
+	};
+}
+
+macro_rules! stack_tuple {
+	($($name:ident $index:tt),+) => {
+		stack!(Sprite, [P: PixelFormat, $($name: Sprite<P>),+] ($($name,)+), |this| {
+			IntoIterator::into_iter([$(&this.$index as &dyn Sprite<P>),+])
+		});
+		stack!(Effect, [P: PixelFormat, $($name: Effect<P>),+] ($($name,)+), |this| {
+			IntoIterator::into_iter([$(&this.$index as &dyn Effect<P>),+])
+		});
+	};
+}
+stack_tuple!(A 0);
+stack_tuple!(A 0, B 1);
+stack_tuple!(A 0, B 1, C 2);
+stack_tuple!(A 0, B 1, C 2, D 3);
+stack_tuple!(A 0, B 1, C 2, D 3, E 4);
+stack_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+stack_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+stack_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+stack!(Sprite, [T: Sprite<P>, P: PixelFormat] [T], |this| this
+	.iter()
+	.map(|layer| layer as &dyn Sprite<P>));
+stack!(Effect, [T: Effect<P>, P: PixelFormat] [T], |this| this
+	.iter()
+	.map(|layer| layer as &dyn Effect<P>));
+stack!(Sprite, [T: Sprite<P>, P: PixelFormat, const N: usize] [T; N], |this| this
+	.iter()
+	.map(|layer| layer as &dyn Sprite<P>));
+stack!(Effect, [T: Effect<P>, P: PixelFormat, const N: usize] [T; N], |this| this
+	.iter()
+	.map(|layer| layer as &dyn Effect<P>));
+stack!(Sprite, [T: Sprite<P>, P: PixelFormat] Vec<T>, |this| this
+	.iter()
+	.map(|layer| layer as &dyn Sprite<P>));
+stack!(Effect, [T: Effect<P>, P: PixelFormat] Vec<T>, |this| this
+	.iter()
+	.map(|layer| layer as &dyn Effect<P>));
+
+/// Gets the smallest range containing all non-empty `ranges`.
+fn union(ranges: impl Iterator<Item = Range<isize>>) -> Range<isize> {
+	ranges
+		.filter(|range| !range.is_empty())
+		.reduce(|a, b| min(a.start, b.start)..max(a.end, b.end))
+		.unwrap_or(0..0)
+}
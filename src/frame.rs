@@ -0,0 +1,315 @@
+//! Whole-frame rendering of [`Scene`]s, line by line or in tiles.
+
+use crate::{scene::Scene, Effect, PixelFormat, Sprite};
+use std::{convert::TryInto, iter::FusedIterator, ops::Range};
+
+/// A `width` by `height` pixel view of a [`Scene`], starting at its origin.
+///
+/// Lines are rendered with `all_lines_range` set to [`Frame::all_lines_range`] and span the whole width,
+/// so drawables that depend on either see the same values for each line.
+pub struct Frame<'a, P: PixelFormat, S: ?Sized = dyn Sprite<P>, E: ?Sized = dyn Effect<P>> {
+	scene: &'a Scene<P, S, E>,
+	width: usize,
+	height: usize,
+	all_lines_range: Range<isize>,
+	line_span: Range<isize>,
+}
+
+impl<'a, P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> Frame<'a, P, S, E> {
+	/// Creates a new [`Frame`] instance.
+	///
+	/// # Panics
+	///
+	/// Iff `width` or `height` don't fit into an [`isize`].
+	#[must_use]
+	pub fn new(scene: &'a Scene<P, S, E>, width: usize, height: usize) -> Self {
+		Self {
+			scene,
+			width,
+			height,
+			all_lines_range: 0..height.try_into().expect("`height` too large"),
+			line_span: 0..width.try_into().expect("`width` too large"),
+		}
+	}
+
+	/// Gets the rendered scene.
+	#[must_use]
+	pub fn scene(&self) -> &'a Scene<P, S, E> {
+		self.scene
+	}
+
+	/// Gets the width in pixels.
+	#[must_use]
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// Gets the height in pixels.
+	#[must_use]
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// Gets the number of bytes in each line, rounded up to whole bytes.
+	#[must_use]
+	#[allow(clippy::manual_div_ceil)]
+	pub fn line_len(&self) -> usize {
+		(self.width * P::PIXEL_STRIDE_BITS + 7) / 8
+	}
+
+	/// Gets the `all_lines_range` passed to drawables, which is `0..height`.
+	#[must_use]
+	pub fn all_lines_range(&self) -> Range<isize> {
+		self.all_lines_range.clone()
+	}
+
+	/// Renders line `line` into `buffer`, which is cleared to transparent (all zero) first.
+	///
+	/// # Panics
+	///
+	/// Iff `line` is out of range or `buffer` is shorter than [`Frame::line_len`].
+	pub fn render_line(&self, line: usize, buffer: &mut [u8]) {
+		assert!(line < self.height, "`line` out of range");
+		let buffer = &mut buffer[..self.line_len()];
+		buffer.fill(0);
+
+		self.scene.render_segment(
+			&Some(self.all_lines_range()),
+			line.try_into().expect("infallible"),
+			self.line_span.clone(),
+			self.line_span.clone(),
+			buffer,
+		)
+	}
+
+	/// Renders each line in turn into `buffer` and passes it to `f`, along with its index.
+	///
+	/// This avoids allocating per line, for example when streaming an image.
+	///
+	/// # Panics
+	///
+	/// Iff `buffer` is shorter than [`Frame::line_len`].
+	pub fn for_each_line(&self, buffer: &mut [u8], mut f: impl FnMut(usize, &[u8])) {
+		let buffer = &mut buffer[..self.line_len()];
+		for line in 0..self.height {
+			self.render_line(line, buffer);
+			f(line, buffer);
+		}
+	}
+
+	/// Iterates over the rendered lines, top to bottom.
+	#[must_use]
+	pub fn lines(&self) -> Lines<'_, 'a, P, S, E> {
+		Lines {
+			frame: self,
+			remaining: 0..self.height,
+		}
+	}
+
+	/// Renders the rectangle of `lines` and `columns` into `buffer`, row by row without padding.
+	///
+	/// `buffer` is cleared to transparent (all zero) first.
+	/// Each row is rendered as a segment of the full-width line, so the pixels are the same as in [`Frame::render_line`].
+	///
+	/// # Panics
+	///
+	/// - Iff [`P::PIXEL_STRIDE_BITS`](`PixelFormat::PIXEL_STRIDE_BITS`) isn't a multiple of 8,
+	/// - iff `lines` or `columns` extend beyond the frame,
+	/// - or iff `buffer` is too short to hold the rectangle.
+	pub fn render_rect(&self, lines: Range<usize>, columns: Range<usize>, buffer: &mut [u8]) {
+		assert_eq!(P::PIXEL_STRIDE_BITS % 8, 0);
+		assert!(lines.end <= self.height, "`lines` out of range");
+		assert!(columns.end <= self.width, "`columns` out of range");
+
+		let row_len = columns.len() * (P::PIXEL_STRIDE_BITS / 8);
+		let buffer = &mut buffer[..lines.len() * row_len];
+		buffer.fill(0);
+		if row_len == 0 {
+			return;
+		}
+
+		let segment_span: Range<isize> = columns.start.try_into().expect("infallible")
+			..columns.end.try_into().expect("infallible");
+		for (line, row) in lines.zip(buffer.chunks_exact_mut(row_len)) {
+			self.scene.render_segment(
+				&Some(self.all_lines_range()),
+				line.try_into().expect("infallible"),
+				self.line_span.clone(),
+				segment_span.clone(),
+				row,
+			)
+		}
+	}
+
+	/// Iterates over the frame in tiles of up to `tile_width` by `tile_height` pixels, in raster order.
+	///
+	/// Tiles along the right and bottom edges are cut off at the frame's bounds.
+	/// Only one tile's worth of each line is rendered at a time, which keeps very wide frames cache-friendly.
+	///
+	/// # Panics
+	///
+	/// - Iff [`P::PIXEL_STRIDE_BITS`](`PixelFormat::PIXEL_STRIDE_BITS`) isn't a multiple of 8,
+	/// - or iff `tile_width` or `tile_height` is zero.
+	#[must_use]
+	pub fn tiles(&self, tile_width: usize, tile_height: usize) -> Tiles<'_, 'a, P, S, E> {
+		assert_eq!(P::PIXEL_STRIDE_BITS % 8, 0);
+		assert!(tile_width > 0, "`tile_width` must not be zero");
+		assert!(tile_height > 0, "`tile_height` must not be zero");
+
+		#[allow(clippy::manual_div_ceil)]
+		let (columns, rows) = (
+			(self.width + tile_width - 1) / tile_width,
+			(self.height + tile_height - 1) / tile_height,
+		);
+		Tiles {
+			frame: self,
+			tile_width,
+			tile_height,
+			columns,
+			remaining: 0..columns * rows,
+		}
+	}
+}
+
+/// A rendered rectangular part of a [`Frame`]. See [`Frame::tiles`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tile {
+	/// The tile's column in the grid of tiles.
+	pub column: usize,
+	/// The tile's row in the grid of tiles.
+	pub row: usize,
+	/// The left edge in the frame, in pixels.
+	pub x: usize,
+	/// The top edge in the frame, in pixels.
+	pub y: usize,
+	/// The width in pixels.
+	pub width: usize,
+	/// The height in pixels.
+	pub height: usize,
+	/// The pixels, row by row without padding.
+	pub data: Vec<u8>,
+}
+
+/// An iterator over the tiles of a [`Frame`], each freshly allocated. See [`Frame::tiles`].
+pub struct Tiles<'f, 'a, P: PixelFormat, S: ?Sized, E: ?Sized> {
+	frame: &'f Frame<'a, P, S, E>,
+	tile_width: usize,
+	tile_height: usize,
+	columns: usize,
+	/// Indices in raster order.
+	remaining: Range<usize>,
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> Tiles<'_, '_, P, S, E> {
+	fn render(&self, index: usize) -> Tile {
+		let (row, column) = (index / self.columns, index % self.columns);
+		let (x, y) = (column * self.tile_width, row * self.tile_height);
+		let width = self.tile_width.min(self.frame.width - x);
+		let height = self.tile_height.min(self.frame.height - y);
+
+		let mut data = vec![0; width * height * (P::PIXEL_STRIDE_BITS / 8)];
+		self.frame
+			.render_rect(y..y + height, x..x + width, &mut data);
+		Tile {
+			column,
+			row,
+			x,
+			y,
+			width,
+			height,
+			data,
+		}
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> Iterator
+	for Tiles<'_, '_, P, S, E>
+{
+	type Item = Tile;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let index = self.remaining.next()?;
+		Some(self.render(index))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.remaining.size_hint()
+	}
+
+	fn nth(&mut self, n: usize) -> Option<Self::Item> {
+		let index = self.remaining.nth(n)?;
+		Some(self.render(index))
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> DoubleEndedIterator
+	for Tiles<'_, '_, P, S, E>
+{
+	fn next_back(&mut self) -> Option<Self::Item> {
+		let index = self.remaining.next_back()?;
+		Some(self.render(index))
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> ExactSizeIterator
+	for Tiles<'_, '_, P, S, E>
+{
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> FusedIterator
+	for Tiles<'_, '_, P, S, E>
+{
+}
+
+/// An iterator over the lines of a [`Frame`], each freshly allocated. See [`Frame::lines`].
+pub struct Lines<'f, 'a, P: PixelFormat, S: ?Sized, E: ?Sized> {
+	frame: &'f Frame<'a, P, S, E>,
+	remaining: Range<usize>,
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> Lines<'_, '_, P, S, E> {
+	fn render(&self, line: usize) -> Vec<u8> {
+		let mut buffer = vec![0; self.frame.line_len()];
+		self.frame.render_line(line, &mut buffer);
+		buffer
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> Iterator
+	for Lines<'_, '_, P, S, E>
+{
+	type Item = Vec<u8>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let line = self.remaining.next()?;
+		Some(self.render(line))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.remaining.size_hint()
+	}
+
+	fn nth(&mut self, n: usize) -> Option<Self::Item> {
+		let line = self.remaining.nth(n)?;
+		Some(self.render(line))
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> DoubleEndedIterator
+	for Lines<'_, '_, P, S, E>
+{
+	fn next_back(&mut self) -> Option<Self::Item> {
+		let line = self.remaining.next_back()?;
+		Some(self.render(line))
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> ExactSizeIterator
+	for Lines<'_, '_, P, S, E>
+{
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> FusedIterator
+	for Lines<'_, '_, P, S, E>
+{
+}
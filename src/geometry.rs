@@ -0,0 +1,176 @@
+//! Fractional geometry used by the vector drawables.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+mod path;
+mod svg_path;
+pub use path::Path;
+pub use svg_path::ParseSvgPathError;
+
+/// A point or vector, in fractional pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+	/// Rightwards coordinate.
+	pub x: f32,
+	/// Downwards coordinate.
+	pub y: f32,
+}
+impl Point {
+	/// Creates a new [`Point`] instance.
+	#[must_use]
+	pub const fn new(x: f32, y: f32) -> Self {
+		Self { x, y }
+	}
+
+	/// Calculates the dot product with `rhs`.
+	#[must_use]
+	pub fn dot(self, rhs: Self) -> f32 {
+		self.x * rhs.x + self.y * rhs.y
+	}
+
+	/// Calculates the z component of the cross product with `rhs`.
+	#[must_use]
+	pub fn cross(self, rhs: Self) -> f32 {
+		self.x * rhs.y - self.y * rhs.x
+	}
+
+	/// Calculates the Euclidean length of this vector.
+	#[must_use]
+	pub fn length(self) -> f32 {
+		self.dot(self).sqrt()
+	}
+}
+impl Add for Point {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self::new(self.x + rhs.x, self.y + rhs.y)
+	}
+}
+impl Sub for Point {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self::new(self.x - rhs.x, self.y - rhs.y)
+	}
+}
+impl Mul<f32> for Point {
+	type Output = Self;
+
+	fn mul(self, rhs: f32) -> Self::Output {
+		Self::new(self.x * rhs, self.y * rhs)
+	}
+}
+impl Neg for Point {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		Self::new(-self.x, -self.y)
+	}
+}
+
+/// Gets the centre of the pixel at `x` along either axis.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn pixel_center(x: isize) -> f32 {
+	x as f32 + 0.5
+}
+
+/// Gets the index of the pixel containing `x`, saturating at the extremes.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn floor(x: f32) -> isize {
+	x.floor() as isize
+}
+
+/// Gets the index of the first pixel starting at or after `x`, saturating at the extremes.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn ceil(x: f32) -> isize {
+	x.ceil() as isize
+}
+
+/// A 2D affine transformation, mapping `(x, y)` to `(a·x + c·y + e, b·x + d·y + f)` like SVG's `matrix(a b c d e f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+	#[allow(missing_docs)]
+	pub a: f32,
+	#[allow(missing_docs)]
+	pub b: f32,
+	#[allow(missing_docs)]
+	pub c: f32,
+	#[allow(missing_docs)]
+	pub d: f32,
+	#[allow(missing_docs)]
+	pub e: f32,
+	#[allow(missing_docs)]
+	pub f: f32,
+}
+impl Default for Transform {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+impl Transform {
+	/// The transformation that leaves all points in place.
+	pub const IDENTITY: Self = Self {
+		a: 1.,
+		b: 0.,
+		c: 0.,
+		d: 1.,
+		e: 0.,
+		f: 0.,
+	};
+
+	/// Creates a translation by `offset`.
+	#[must_use]
+	pub fn translate(offset: Point) -> Self {
+		Self {
+			e: offset.x,
+			f: offset.y,
+			..Self::IDENTITY
+		}
+	}
+
+	/// Creates a scaling around the origin.
+	#[must_use]
+	pub fn scale(x: f32, y: f32) -> Self {
+		Self {
+			a: x,
+			d: y,
+			..Self::IDENTITY
+		}
+	}
+
+	/// Creates a clockwise rotation around the origin by `angle` radians.
+	#[must_use]
+	pub fn rotate(angle: f32) -> Self {
+		let (sin, cos) = angle.sin_cos();
+		Self {
+			a: cos,
+			b: sin,
+			c: -sin,
+			d: cos,
+			..Self::IDENTITY
+		}
+	}
+
+	/// Combines this transformation with `next`, which is applied afterwards.
+	#[must_use]
+	pub fn then(self, next: Self) -> Self {
+		Self {
+			a: next.a * self.a + next.c * self.b,
+			b: next.b * self.a + next.d * self.b,
+			c: next.a * self.c + next.c * self.d,
+			d: next.b * self.c + next.d * self.d,
+			e: next.a * self.e + next.c * self.f + next.e,
+			f: next.b * self.e + next.d * self.f + next.f,
+		}
+	}
+
+	/// Transforms `point`.
+	#[must_use]
+	pub fn apply(&self, point: Point) -> Point {
+		Point::new(
+			self.a * point.x + self.c * point.y + self.e,
+			self.b * point.x + self.d * point.y + self.f,
+		)
+	}
+}
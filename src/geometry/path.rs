@@ -0,0 +1,268 @@
+use super::{Point, Transform};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+	MoveTo(Point),
+	LineTo(Point),
+	QuadTo(Point, Point),
+	CubicTo(Point, Point, Point),
+	Close,
+}
+
+/// A vector path made of any number of subpaths with straight and Bézier curve segments.
+///
+/// Paths are built by chaining calls, starting from [`Path::new`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Path {
+	commands: Vec<Command>,
+}
+impl Path {
+	/// Creates a new empty [`Path`] instance.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Starts a new subpath at `to`.
+	#[must_use]
+	pub fn move_to(mut self, to: Point) -> Self {
+		self.commands.push(Command::MoveTo(to));
+		self
+	}
+
+	/// Adds a straight line to `to`.
+	#[must_use]
+	pub fn line_to(mut self, to: Point) -> Self {
+		self.commands.push(Command::LineTo(to));
+		self
+	}
+
+	/// Adds a quadratic Bézier curve to `to`.
+	#[must_use]
+	pub fn quad_to(mut self, control: Point, to: Point) -> Self {
+		self.commands.push(Command::QuadTo(control, to));
+		self
+	}
+
+	/// Adds a cubic Bézier curve to `to`.
+	#[must_use]
+	pub fn cubic_to(mut self, control_1: Point, control_2: Point, to: Point) -> Self {
+		self.commands
+			.push(Command::CubicTo(control_1, control_2, to));
+		self
+	}
+
+	/// Adds an elliptical arc to `to`, as in SVG path data.
+	///
+	/// The ellipse has the given `radii`, rotated clockwise by `rotation` radians.
+	/// `large_arc` and `sweep` (clockwise) select which of the up to four matching arcs is drawn.
+	/// Radii too small to reach `to` are scaled up uniformly, and a zero radius results in a straight line.
+	#[must_use]
+	pub fn arc_to(
+		self,
+		radii: Point,
+		rotation: f32,
+		large_arc: bool,
+		sweep: bool,
+		to: Point,
+	) -> Self {
+		let from = self.current_point();
+		if from == to {
+			return self;
+		}
+		let (mut r_x, mut r_y) = (radii.x.abs(), radii.y.abs());
+		#[allow(clippy::float_cmp)]
+		if r_x == 0. || r_y == 0. {
+			return self.line_to(to);
+		}
+
+		// See <https://www.w3.org/TR/SVG11/implnote.html#ArcConversionEndpointToCenter>.
+		let to_ellipse = Transform::rotate(-rotation);
+		let half = to_ellipse.apply((from - to) * 0.5);
+		let lambda = (half.x * half.x) / (r_x * r_x) + (half.y * half.y) / (r_y * r_y);
+		if lambda > 1. {
+			r_x *= lambda.sqrt();
+			r_y *= lambda.sqrt();
+		}
+
+		let numerator =
+			r_x * r_x * r_y * r_y - r_x * r_x * half.y * half.y - r_y * r_y * half.x * half.x;
+		let denominator = r_x * r_x * half.y * half.y + r_y * r_y * half.x * half.x;
+		let sign = if large_arc == sweep { -1. } else { 1. };
+		let coefficient = sign * (numerator / denominator).max(0.).sqrt();
+		let center = Point::new(
+			coefficient * r_x * half.y / r_y,
+			-coefficient * r_y * half.x / r_x,
+		);
+
+		let start = Point::new((half.x - center.x) / r_x, (half.y - center.y) / r_y);
+		let end = Point::new((-half.x - center.x) / r_x, (-half.y - center.y) / r_y);
+		let start_angle = start.y.atan2(start.x);
+		let mut sweep_angle = end.y.atan2(end.x) - start_angle;
+		if sweep && sweep_angle < 0. {
+			sweep_angle += TAU;
+		} else if !sweep && sweep_angle > 0. {
+			sweep_angle -= TAU;
+		}
+
+		let from_unit_circle = Transform::scale(r_x, r_y)
+			.then(Transform::translate(center))
+			.then(Transform::rotate(rotation))
+			.then(Transform::translate((from + to) * 0.5));
+
+		// Each cubic Bézier curve approximates at most a quarter turn.
+		let count = segment_count(sweep_angle.abs() / FRAC_PI_2);
+		let step = sweep_angle / fraction(count, 1);
+		let handle = 4. / 3. * (step / 4.).tan();
+		let mut path = self;
+		for i in 0..count {
+			let (angle_0, angle_1) = (
+				start_angle + step * fraction(i, 1),
+				start_angle + step * fraction(i + 1, 1),
+			);
+			let (sin_0, cos_0) = angle_0.sin_cos();
+			let (sin_1, cos_1) = angle_1.sin_cos();
+			path = path.cubic_to(
+				from_unit_circle.apply(Point::new(cos_0 - handle * sin_0, sin_0 + handle * cos_0)),
+				from_unit_circle.apply(Point::new(cos_1 + handle * sin_1, sin_1 - handle * cos_1)),
+				if i + 1 == count {
+					to
+				} else {
+					from_unit_circle.apply(Point::new(cos_1, sin_1))
+				},
+			);
+		}
+		path
+	}
+
+	/// Closes the current subpath with a straight line back to its start.
+	#[must_use]
+	pub fn close(mut self) -> Self {
+		self.commands.push(Command::Close);
+		self
+	}
+
+	/// Applies `transform` to all points of this path.
+	#[must_use]
+	pub fn transform(&self, transform: &Transform) -> Self {
+		let apply = |point| transform.apply(point);
+		Self {
+			commands: self
+				.commands
+				.iter()
+				.map(|command| match *command {
+					Command::MoveTo(to) => Command::MoveTo(apply(to)),
+					Command::LineTo(to) => Command::LineTo(apply(to)),
+					Command::QuadTo(control, to) => Command::QuadTo(apply(control), apply(to)),
+					Command::CubicTo(control_1, control_2, to) => {
+						Command::CubicTo(apply(control_1), apply(control_2), apply(to))
+					}
+					Command::Close => Command::Close,
+				})
+				.collect(),
+		}
+	}
+
+	/// Gets the point the next segment starts from.
+	pub(crate) fn current_point(&self) -> Point {
+		match self.commands.last() {
+			None => Point::default(),
+			Some(
+				Command::MoveTo(to)
+				| Command::LineTo(to)
+				| Command::QuadTo(_, to)
+				| Command::CubicTo(_, _, to),
+			) => *to,
+			Some(Command::Close) => self.subpath_start(),
+		}
+	}
+
+	/// Gets the start of the current (or, after [`Path::close`], the next) subpath.
+	fn subpath_start(&self) -> Point {
+		self.commands
+			.iter()
+			.rev()
+			.find_map(|command| match *command {
+				Command::MoveTo(to) => Some(to),
+				_ => None,
+			})
+			.unwrap_or_default()
+	}
+
+	/// Approximates each subpath as polyline, flagged as closed or not.
+	///
+	/// Curves deviate from their polyline by at most `tolerance` pixels.
+	/// Subpaths not started by [`Path::move_to`] begin where the previous one was closed, or at the origin.
+	pub(crate) fn flatten(&self, tolerance: f32) -> Vec<(Vec<Point>, bool)> {
+		let mut polylines = Vec::new();
+		let mut current = vec![Point::default()];
+
+		for command in &self.commands {
+			let last = *current.last().expect("always non-empty");
+			match *command {
+				Command::MoveTo(to) => {
+					finish(&mut polylines, &mut current, false, to);
+				}
+				Command::LineTo(to) => current.push(to),
+				Command::QuadTo(control, to) => {
+					let curvature = (last - control * 2. + to).length();
+					let count = segment_count((curvature / (4. * tolerance)).sqrt());
+					current.extend((1..=count).map(|i| {
+						let t = fraction(i, count);
+						let u = 1. - t;
+						last * (u * u) + control * (2. * u * t) + to * (t * t)
+					}));
+				}
+				Command::CubicTo(control_1, control_2, to) => {
+					let curvature = (last - control_1 * 2. + control_2)
+						.length()
+						.max((control_1 - control_2 * 2. + to).length());
+					let count = segment_count((3. * curvature / (4. * tolerance)).sqrt());
+					current.extend((1..=count).map(|i| {
+						let t = fraction(i, count);
+						let u = 1. - t;
+						last * (u * u * u)
+							+ control_1 * (3. * u * u * t)
+							+ control_2 * (3. * u * t * t)
+							+ to * (t * t * t)
+					}));
+				}
+				Command::Close => {
+					let start = current[0];
+					finish(&mut polylines, &mut current, true, start);
+				}
+			}
+		}
+
+		finish(&mut polylines, &mut current, false, Point::default());
+		polylines
+	}
+}
+
+/// Moves `current` into `polylines` unless it's a lone point, then restarts it at `next_start`.
+fn finish(
+	polylines: &mut Vec<(Vec<Point>, bool)>,
+	current: &mut Vec<Point>,
+	closed: bool,
+	next_start: Point,
+) {
+	let polyline = std::mem::replace(current, vec![next_start]);
+	if polyline.len() > 1 {
+		polylines.push((polyline, closed));
+	}
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn segment_count(estimate: f32) -> usize {
+	if estimate.is_finite() {
+		(estimate.ceil() as usize).clamp(1, 1024)
+	} else {
+		1024
+	}
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn fraction(numerator: usize, denominator: usize) -> f32 {
+	numerator as f32 / denominator as f32
+}
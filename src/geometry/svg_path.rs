@@ -0,0 +1,236 @@
+use super::{Path, Point};
+use std::{error::Error, f32::consts::PI, fmt, str::FromStr};
+
+/// An error encountered while parsing [SVG path data](https://www.w3.org/TR/SVG11/paths.html#PathData).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseSvgPathError {
+	position: usize,
+	expected: &'static str,
+}
+impl ParseSvgPathError {
+	/// Gets the byte offset at which parsing failed.
+	#[must_use]
+	pub fn position(&self) -> usize {
+		self.position
+	}
+}
+impl fmt::Display for ParseSvgPathError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"expected {} at byte {} of SVG path data",
+			self.expected, self.position
+		)
+	}
+}
+impl Error for ParseSvgPathError {}
+
+/// Parses [SVG path data](https://www.w3.org/TR/SVG11/paths.html#PathData), like the `d` attribute of a `<path>`.
+///
+/// All commands are supported, including relative, shorthand and arc ones.
+impl FromStr for Path {
+	type Err = ParseSvgPathError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parser = Parser {
+			bytes: s.as_bytes(),
+			position: 0,
+		};
+		let mut path = Path::new();
+		let mut command = None;
+		// The reflectable control point of the previous cubic (`true`) or quadratic (`false`) curve.
+		let mut last_control: Option<(bool, Point)> = None;
+
+		loop {
+			parser.skip_separators();
+			let letter_position = parser.position;
+			let letter = match parser.peek() {
+				None => break,
+				Some(byte) if byte.is_ascii_alphabetic() => {
+					parser.position += 1;
+					byte
+				}
+				Some(_) => match command {
+					Some(command) if !matches!(command, b'Z' | b'z') => command,
+					_ => return Err(parser.error("a command")),
+				},
+			};
+			if command.is_none() && !matches!(letter, b'M' | b'm') {
+				return Err(ParseSvgPathError {
+					position: letter_position,
+					expected: "a move-to command",
+				});
+			}
+
+			let current = path.current_point();
+			let relative = letter.is_ascii_lowercase();
+			let base = if relative { current } else { Point::default() };
+			let mut control = None;
+
+			// Further coordinate pairs after a move-to are line-tos.
+			command = Some(match letter {
+				b'M' | b'm' => {
+					path = path.move_to(base + parser.point()?);
+					if relative {
+						b'l'
+					} else {
+						b'L'
+					}
+				}
+				b'L' | b'l' => {
+					path = path.line_to(base + parser.point()?);
+					letter
+				}
+				b'H' | b'h' => {
+					path = path.line_to(Point::new(base.x + parser.number()?, current.y));
+					letter
+				}
+				b'V' | b'v' => {
+					path = path.line_to(Point::new(current.x, base.y + parser.number()?));
+					letter
+				}
+				b'C' | b'c' | b'S' | b's' => {
+					let control_1 = if matches!(letter, b'C' | b'c') {
+						base + parser.point()?
+					} else {
+						reflect(last_control, true, current)
+					};
+					let control_2 = base + parser.point()?;
+					let to = base + parser.point()?;
+					path = path.cubic_to(control_1, control_2, to);
+					control = Some((true, control_2));
+					letter
+				}
+				b'Q' | b'q' | b'T' | b't' => {
+					let control_1 = if matches!(letter, b'Q' | b'q') {
+						base + parser.point()?
+					} else {
+						reflect(last_control, false, current)
+					};
+					let to = base + parser.point()?;
+					path = path.quad_to(control_1, to);
+					control = Some((false, control_1));
+					letter
+				}
+				b'A' | b'a' => {
+					let radii = parser.point()?;
+					let rotation = parser.number()? * PI / 180.;
+					let large_arc = parser.flag()?;
+					let sweep = parser.flag()?;
+					let to = base + parser.point()?;
+					path = path.arc_to(radii, rotation, large_arc, sweep, to);
+					letter
+				}
+				b'Z' | b'z' => {
+					// Subsequent relative coordinates are based on the closed subpath's start.
+					path = path.close();
+					letter
+				}
+				_ => {
+					return Err(ParseSvgPathError {
+						position: letter_position,
+						expected: "a command",
+					})
+				}
+			});
+			last_control = control;
+		}
+
+		Ok(path)
+	}
+}
+
+/// Reflects the previous control point of the same curve kind around `current`, or uses `current` if there is none.
+fn reflect(last_control: Option<(bool, Point)>, cubic: bool, current: Point) -> Point {
+	match last_control {
+		Some((last_cubic, control)) if last_cubic == cubic => current * 2. - control,
+		_ => current,
+	}
+}
+
+struct Parser<'a> {
+	bytes: &'a [u8],
+	position: usize,
+}
+impl Parser<'_> {
+	fn error(&self, expected: &'static str) -> ParseSvgPathError {
+		ParseSvgPathError {
+			position: self.position,
+			expected,
+		}
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.bytes.get(self.position).copied()
+	}
+
+	fn skip_separators(&mut self) {
+		while let Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C' | b',') = self.peek() {
+			self.position += 1;
+		}
+	}
+
+	fn point(&mut self) -> Result<Point, ParseSvgPathError> {
+		Ok(Point::new(self.number()?, self.number()?))
+	}
+
+	/// Parses a flag, which doesn't require separators after it.
+	fn flag(&mut self) -> Result<bool, ParseSvgPathError> {
+		self.skip_separators();
+		let flag = match self.peek() {
+			Some(b'0') => false,
+			Some(b'1') => true,
+			_ => return Err(self.error("a flag")),
+		};
+		self.position += 1;
+		Ok(flag)
+	}
+
+	fn number(&mut self) -> Result<f32, ParseSvgPathError> {
+		self.skip_separators();
+		let start = self.position;
+
+		if let Some(b'+' | b'-') = self.peek() {
+			self.position += 1;
+		}
+		let integer_digits = self.digits();
+		let fraction_digits = if self.peek() == Some(b'.') {
+			self.position += 1;
+			self.digits()
+		} else {
+			0
+		};
+		if integer_digits == 0 && fraction_digits == 0 {
+			self.position = start;
+			return Err(self.error("a number"));
+		}
+
+		if let Some(b'e' | b'E') = self.peek() {
+			let mantissa_end = self.position;
+			self.position += 1;
+			if let Some(b'+' | b'-') = self.peek() {
+				self.position += 1;
+			}
+			if self.digits() == 0 {
+				// Not an exponent after all.
+				self.position = mantissa_end;
+			}
+		}
+
+		std::str::from_utf8(&self.bytes[start..self.position])
+			.expect("ASCII")
+			.parse()
+			.map_err(|_| ParseSvgPathError {
+				position: start,
+				expected: "a number",
+			})
+	}
+
+	fn digits(&mut self) -> usize {
+		let start = self.position;
+		while let Some(b'0'..=b'9') = self.peek() {
+			self.position += 1;
+		}
+		self.position - start
+	}
+}
@@ -4,11 +4,16 @@
 //! [![Zulip Chat](https://img.shields.io/endpoint?label=chat&url=https%3A%2F%2Fiteration-square-automation.schichler.dev%2F.netlify%2Ffunctions%2Fstream_subscribers_shield%3Fstream%3Dproject%252Fscanline)](https://iteration-square.schichler.dev/#narrow/stream/project.2Fscanline)
 //!
 //! Coordinates in this crate grow rightwards and downwards, and are in pixels unless otherwise noted.
+//!
+//! # Features
+//!
+//! - `rayon`: Parallel rendering into a [`Canvas`](`canvas::Canvas`), with `Canvas::par_render` and `Canvas::par_render_rect`.
 
 #![doc(html_root_url = "https://docs.rs/scanline/0.0.1")]
 #![warn(clippy::pedantic, missing_docs)]
 #![allow(clippy::semicolon_if_nothing_returned)]
 
+use coverage::Coverage;
 use std::{
 	cmp::{max, min, Ordering},
 	convert::TryInto,
@@ -21,8 +26,18 @@ use tap::TryConv;
 #[doc = include_str!("../README.md")]
 mod readme {}
 
+mod blend;
+pub mod canvas;
+pub mod combinators;
+mod coverage;
 pub mod drawables;
+mod forwarding;
+pub mod frame;
+pub mod geometry;
 pub mod pixel_formats;
+mod raster;
+pub mod scene;
+pub mod text;
 
 /// Defines a pixel format for the output buffer.
 pub trait PixelFormat {
@@ -33,6 +48,9 @@ pub trait PixelFormat {
 /// All coordinates are effect-relative and in pixels.
 ///
 /// [`Effect`]s are drawn back-to-front after sprites into a buffer, with premultiplied alpha (if applicable).
+///
+/// Smart pointers and [`Option`]s of effects are effects too, with [`None`] rendering nothing.
+/// So are tuples, arrays, slices and [`Vec`]s of them, as stacks drawn back-to-front in order.
 pub trait Effect<P: PixelFormat> {
 	/// Gets the applicable line range.
 	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize>;
@@ -101,6 +119,10 @@ where
 /// All coordinates are sprite-relative and in pixels.
 ///
 /// [`Sprite`]s are drawn front to back into a buffer, with premultiplied alpha (if applicable).
+/// Pixels already covered by [opaque segments](`Sprite::opaque_segment`) of sprites in front aren't rendered again.
+///
+/// Smart pointers and [`Option`]s of sprites are sprites too, with [`None`] rendering nothing.
+/// So are tuples, arrays, slices and [`Vec`]s of them, as stacks drawn front to back in order.
 pub trait Sprite<P: PixelFormat> {
 	/// Gets the applicable line range.
 	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize>;
@@ -113,6 +135,19 @@ pub trait Sprite<P: PixelFormat> {
 		line_span: Range<isize>,
 	) -> Range<isize>;
 
+	/// Gets a part of the line segment that this sprite is known to render fully opaque.
+	///
+	/// [`render_segment`] skips whatever is behind it in later sprites.
+	/// The default is an empty range, which is always correct.
+	fn opaque_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		0..0
+	}
+
 	/// Renders the given segment of the given line. The relevant data is offset `offset_bits` into `data`.
 	///
 	/// `offset_bits` can be relied on to be a multiple of `P::BITS_PER_PIXEL` modulo 8.
@@ -143,6 +178,15 @@ where
 		T::line_segment(self, all_lines_range, line, line_span)
 	}
 
+	fn opaque_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		T::opaque_segment(self, all_lines_range, line, line_span)
+	}
+
 	fn render(
 		&self,
 		all_lines_range: Option<Range<isize>>,
@@ -211,6 +255,10 @@ pub fn render_line<
 
 /// Renders a segment of a line.
 ///
+/// `buffer` holds only the pixels in `segment_span`, starting [`P::PIXEL_STRIDE_BITS`](`PixelFormat::PIXEL_STRIDE_BITS`)
+/// times `segment_span.start` bits (modulo 8) into its first byte.
+/// Pixel `x` then starts `(x - segment_span.start) * P::PIXEL_STRIDE_BITS` bits further in.
+///
 /// # Panics
 ///
 /// Iff coordinates and/or sizes are extreme enough to go out of range.
@@ -239,16 +287,22 @@ pub fn render_segment<
 				.expect("`PIXEL_STRIDE_BITS` too large"),
 		)
 		.expect("segment offset in bits too extreme")
-		% 8;
+		.rem_euclid(8);
 	let segment_offset_bits: usize = segment_offset_bits
 		.try_into()
 		.expect("extreme pixel stride caused segment offset overflow");
 
-	assert!(
-		buffer.len() >= (segment_span.len() * P::PIXEL_STRIDE_BITS + segment_offset_bits + 7) / 8
-	);
+	#[allow(clippy::manual_div_ceil)]
+	let segment_len = (segment_span.len() * P::PIXEL_STRIDE_BITS + segment_offset_bits + 7) / 8;
+	assert!(buffer.len() >= segment_len);
 
+	let buffer_span = segment_span.clone();
+	let mut coverage = Coverage::new();
 	for (position, sprite) in sprites {
+		if coverage.covers(&segment_span) {
+			break;
+		}
+
 		let all_lines_range = all_lines_range
 			.clone()
 			.map(|all_lines_range| all_lines_range.offset(-position.y));
@@ -265,28 +319,25 @@ pub fn render_segment<
 			line_index,
 			line_span.clone(),
 		)) {
-			let buffer_clip_pixels = segment_span.clone().offset(position.x);
-			let buffer_clip_pixels: Range<usize> = buffer_clip_pixels
-				.start
-				.try_into()
-				.expect("buffer clip pixels")
-				..buffer_clip_pixels
-					.end
-					.try_into()
-					.expect("buffer clip pixels");
-
-			let buffer_clip =
-				(segment_offset_bits + buffer_clip_pixels.start * P::PIXEL_STRIDE_BITS + 7) / 8
-					..(segment_offset_bits + buffer_clip_pixels.end * P::PIXEL_STRIDE_BITS + 7) / 8;
-
-			sprite.render(
-				all_lines_range,
-				line_index,
-				line_span,
-				segment_span,
-				segment_offset_bits,
-				&mut buffer[buffer_clip],
-			)
+			for gap in coverage.gaps(segment_span.clone().offset(position.x)) {
+				let (bytes, offset_bits) =
+					segment_bytes::<P>(&buffer_span, &gap, segment_offset_bits);
+				sprite.render(
+					all_lines_range.clone(),
+					line_index,
+					line_span.clone(),
+					gap.offset(-position.x),
+					offset_bits,
+					&mut buffer[bytes],
+				)
+			}
+
+			if let Some(opaque) = sprite
+				.opaque_segment(all_lines_range, line_index, line_span)
+				.intersect(segment_span)
+			{
+				coverage.cover(opaque.offset(position.x));
+			}
 		}
 	}
 
@@ -307,32 +358,39 @@ pub fn render_segment<
 			line_index,
 			line_span.clone(),
 		)) {
-			let buffer_clip_pixels = segment_span.clone().offset(position.x);
-			let buffer_clip_pixels: Range<usize> = buffer_clip_pixels
-				.start
-				.try_into()
-				.expect("buffer clip pixels")
-				..buffer_clip_pixels
-					.end
-					.try_into()
-					.expect("buffer clip pixels");
-
-			let buffer_clip =
-				(segment_offset_bits + buffer_clip_pixels.start * P::PIXEL_STRIDE_BITS + 7) / 8
-					..(segment_offset_bits + buffer_clip_pixels.end * P::PIXEL_STRIDE_BITS + 7) / 8;
+			let (bytes, offset_bits) = segment_bytes::<P>(
+				&buffer_span,
+				&segment_span.clone().offset(position.x),
+				segment_offset_bits,
+			);
 
 			effect.render(
 				all_lines_range,
 				line_index,
 				line_span,
 				segment_span,
-				segment_offset_bits,
-				&mut buffer[buffer_clip],
+				offset_bits,
+				&mut buffer[bytes],
 			)
 		}
 	}
 }
 
+/// Gets the bytes and bit offset within them of `sub_segment` in data of `segment` starting at `offset_bits`.
+#[allow(clippy::manual_div_ceil)]
+fn segment_bytes<P: PixelFormat>(
+	segment: &Range<isize>,
+	sub_segment: &Range<isize>,
+	offset_bits: usize,
+) -> (Range<usize>, usize) {
+	let start: usize = (sub_segment.start - segment.start)
+		.try_into()
+		.expect("infallible");
+	let start_bits = offset_bits + start * P::PIXEL_STRIDE_BITS;
+	let end_bits = start_bits + sub_segment.len() * P::PIXEL_STRIDE_BITS;
+	(start_bits / 8..(end_bits + 7) / 8, start_bits % 8)
+}
+
 trait Offset<T: Add<U>, U> {
 	type Output;
 	fn offset(self, scalar: U) -> Self::Output;
@@ -0,0 +1,280 @@
+//! Anti-aliased coverage accumulation shared by the vector drawables.
+
+use crate::{
+	blend,
+	geometry::{ceil, floor, Point},
+	pixel_formats::RgbaNoPadding,
+	Intersect, Sprite,
+};
+use std::{cmp::Ordering, convert::TryInto, ops::Range};
+
+/// Number of sub-scanlines sampled per line. Horizontal coverage is calculated exactly.
+pub(crate) const SUBSAMPLES: usize = 16;
+
+/// Fraction of the line height represented by each sub-scanline.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) const SUBSAMPLE_WEIGHT: f32 = 1. / SUBSAMPLES as f32;
+
+/// Gets the sub-scanline `y` coordinates sampled for `line`, in ascending order.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn sample_ys(line: isize) -> impl Iterator<Item = f32> {
+	(0..SUBSAMPLES).map(move |i| line as f32 + (i as f32 + 0.5) / SUBSAMPLES as f32)
+}
+
+/// Per-pixel coverage of a line segment, accumulated from horizontal spans.
+pub(crate) struct Coverage {
+	origin: isize,
+	/// Partial coverage of individual pixels.
+	area: Vec<f32>,
+	/// Differences of full coverage between neighbouring pixels.
+	cover: Vec<f32>,
+}
+impl Coverage {
+	pub(crate) fn new(segment: Range<isize>) -> Self {
+		Self {
+			origin: segment.start,
+			area: vec![0.; segment.len()],
+			cover: vec![0.; segment.len() + 1],
+		}
+	}
+
+	/// Adds the span `x_0..x_1`, with `weight` being the fraction of the line height it represents.
+	///
+	/// Spans with a non-finite end are skipped.
+	#[allow(
+		clippy::cast_possible_truncation,
+		clippy::cast_precision_loss,
+		clippy::cast_sign_loss
+	)]
+	pub(crate) fn add_span(&mut self, x_0: f32, x_1: f32, weight: f32) {
+		if !(x_0.is_finite() && x_1.is_finite()) {
+			return;
+		}
+
+		let len = self.area.len() as f32;
+		let x_0 = (x_0 - self.origin as f32).max(0.);
+		let x_1 = (x_1 - self.origin as f32).min(len);
+		if x_0.partial_cmp(&x_1) != Some(Ordering::Less) {
+			return;
+		}
+
+		let (i_0, i_1) = (x_0 as usize, x_1 as usize);
+		if i_0 == i_1 {
+			self.area[i_0] += (x_1 - x_0) * weight;
+			return;
+		}
+
+		self.area[i_0] += (i_0 as f32 + 1. - x_0) * weight;
+		self.cover[i_0 + 1] += weight;
+		self.cover[i_1] -= weight;
+		if i_1 < self.area.len() {
+			self.area[i_1] += (x_1 - i_1 as f32) * weight;
+		}
+	}
+
+	/// Resolves the accumulated spans into one coverage value per pixel.
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	pub(crate) fn finish(self) -> Vec<u8> {
+		let mut cover = 0.;
+		self.area
+			.iter()
+			.zip(&self.cover)
+			.map(|(area, delta)| {
+				cover += delta;
+				((cover + area).clamp(0., 1.) * 255. + 0.5) as u8
+			})
+			.collect()
+	}
+}
+
+/// Renders `paint` into a transparent scratch buffer covering `segment`.
+///
+/// Pixels outside of `paint`'s own lines and segments stay transparent.
+pub(crate) fn render_paint<F: Sprite<RgbaNoPadding<8>> + ?Sized>(
+	paint: &F,
+	all_lines_range: Option<Range<isize>>,
+	line: isize,
+	line_span: Range<isize>,
+	segment: Range<isize>,
+) -> Vec<u8> {
+	let mut scratch = vec![0; segment.len() * 4];
+
+	if !paint.lines(all_lines_range.clone()).contains(&line) {
+		return scratch;
+	}
+
+	if let Some(paint_segment) = segment.clone().intersect(paint.line_segment(
+		all_lines_range.clone(),
+		line,
+		line_span.clone(),
+	)) {
+		let start: usize = (paint_segment.start - segment.start)
+			.try_into()
+			.expect("infallible");
+		let end = start + paint_segment.len();
+		paint.render(
+			all_lines_range,
+			line,
+			line_span,
+			paint_segment,
+			0,
+			&mut scratch[start * 4..end * 4],
+		)
+	}
+
+	scratch
+}
+
+/// Blends `paint` into `data`, scaled by per-pixel `coverage` of `segment`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_coverage<F: Sprite<RgbaNoPadding<8>> + ?Sized>(
+	paint: &F,
+	coverage: &[u8],
+	all_lines_range: Option<Range<isize>>,
+	line: isize,
+	line_span: Range<isize>,
+	segment: Range<isize>,
+	data: &mut [u8],
+	blend: fn(&mut [u8], &[u8]),
+) {
+	assert_eq!(segment.len() * 4, data.len());
+	assert_eq!(segment.len(), coverage.len());
+
+	if coverage.iter().all(|coverage| *coverage == 0) {
+		return;
+	}
+
+	let mut paint = render_paint(paint, all_lines_range, line, line_span, segment);
+	for (src, coverage) in paint.chunks_exact_mut(4).zip(coverage) {
+		let scaled = blend::scale_rgba8((&*src).try_into().expect("infallible"), *coverage);
+		src.copy_from_slice(&scaled);
+	}
+	blend(data, &paint)
+}
+
+/// A non-horizontal polygon edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Edge {
+	top: f32,
+	bottom: f32,
+	x_at_top: f32,
+	dx_dy: f32,
+	/// `1` for downwards edges, `-1` for upwards ones.
+	winding: i32,
+}
+impl Edge {
+	fn x_at(&self, y: f32) -> f32 {
+		self.x_at_top + (y - self.top) * self.dx_dy
+	}
+}
+
+/// The edges of any number of closed polygons, which can be filled with anti-aliasing.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Polygons {
+	edges: Vec<Edge>,
+	top: f32,
+	bottom: f32,
+}
+impl Polygons {
+	pub(crate) fn new() -> Self {
+		Self {
+			edges: Vec::new(),
+			top: f32::INFINITY,
+			bottom: f32::NEG_INFINITY,
+		}
+	}
+
+	/// Adds the edge from `from` to `to`. Horizontal edges are ignored.
+	pub(crate) fn add_line(&mut self, from: Point, to: Point) {
+		#[allow(clippy::float_cmp)]
+		if from.y == to.y || from.y.is_nan() || to.y.is_nan() {
+			return;
+		}
+
+		let (upper, lower, winding) = if from.y < to.y {
+			(from, to, 1)
+		} else {
+			(to, from, -1)
+		};
+		self.top = self.top.min(upper.y);
+		self.bottom = self.bottom.max(lower.y);
+		self.edges.push(Edge {
+			top: upper.y,
+			bottom: lower.y,
+			x_at_top: upper.x,
+			dx_dy: (lower.x - upper.x) / (lower.y - upper.y),
+			winding,
+		})
+	}
+
+	/// Adds the closed polygon through `points`.
+	pub(crate) fn add_polygon(&mut self, points: &[Point]) {
+		for (i, from) in points.iter().enumerate() {
+			self.add_line(*from, points[(i + 1) % points.len()]);
+		}
+	}
+
+	pub(crate) fn lines(&self) -> Range<isize> {
+		if self.edges.is_empty() {
+			0..0
+		} else {
+			floor(self.top)..ceil(self.bottom)
+		}
+	}
+
+	/// Gets the pixel range touched by any edge on `line`, which contains all filled pixels.
+	#[allow(clippy::cast_precision_loss)]
+	pub(crate) fn line_segment(&self, line: isize) -> Range<isize> {
+		let (line_top, line_bottom) = (line as f32, line as f32 + 1.);
+		let (left, right) = self
+			.edges
+			.iter()
+			.filter(|edge| edge.top < line_bottom && edge.bottom > line_top)
+			.fold((f32::INFINITY, f32::NEG_INFINITY), |(left, right), edge| {
+				let x_0 = edge.x_at(line_top.max(edge.top));
+				let x_1 = edge.x_at(line_bottom.min(edge.bottom));
+				(left.min(x_0).min(x_1), right.max(x_0).max(x_1))
+			});
+		if left <= right {
+			floor(left)..ceil(right)
+		} else {
+			0..0
+		}
+	}
+
+	/// Calculates the coverage of `segment` on `line`, where `inside` decides the fill based on the winding number.
+	pub(crate) fn coverage(
+		&self,
+		line: isize,
+		segment: Range<isize>,
+		inside: impl Fn(i32) -> bool,
+	) -> Vec<u8> {
+		let mut coverage = Coverage::new(segment);
+		let mut crossings = Vec::new();
+		for y in sample_ys(line) {
+			crossings.clear();
+			crossings.extend(
+				self.edges
+					.iter()
+					.filter(|edge| edge.top <= y && y < edge.bottom)
+					.map(|edge| (edge.x_at(y), edge.winding)),
+			);
+			crossings.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+			let mut winding = 0;
+			let mut span_start = None;
+			for (x, crossing_winding) in &crossings {
+				winding += crossing_winding;
+				match (span_start, inside(winding)) {
+					(None, true) => span_start = Some(*x),
+					(Some(start), false) => {
+						coverage.add_span(start, *x, SUBSAMPLE_WEIGHT);
+						span_start = None;
+					}
+					_ => (),
+				}
+			}
+		}
+		coverage.finish()
+	}
+}
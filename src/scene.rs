@@ -0,0 +1,420 @@
+//! Owned collections of positioned drawables.
+
+use crate::{canvas::Canvas, render_segment, Effect, PixelFormat, Position, Sprite};
+use damage::{Bounds, Damage};
+use index::LineIndex;
+use std::{
+	convert::TryInto,
+	marker::PhantomData,
+	mem,
+	ops::Range,
+	sync::{Arc, Mutex, PoisonError},
+};
+
+mod damage;
+mod index;
+
+/// Identifies a sprite in a [`Scene`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SpriteHandle(u64);
+
+/// Identifies an effect in a [`Scene`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EffectHandle(u64);
+
+/// Boxed sprites and effects with positions and z-order, rendered together.
+///
+/// Drawables with a higher `z` are in front. Among equal `z`, later insertions are in front.
+/// All sprites are drawn under all effects, as with [`render_segment`].
+///
+/// Changes are tracked, so that [`Scene::render_damage`] can update a persistent [`Canvas`] incrementally.
+///
+/// Rendering only visits drawables on the current line, using an index that is rebuilt lazily after the scene changes
+/// or when rendering with a different `all_lines_range`.
+///
+/// By default, drawables must be `'static`. Use for example `Scene<P, dyn Sprite<P> + 'a, dyn Effect<P> + 'a>` to borrow,
+/// or concrete types to avoid dynamic dispatch.
+pub struct Scene<P: PixelFormat, S: ?Sized = dyn Sprite<P>, E: ?Sized = dyn Effect<P>> {
+	/// Front to back.
+	sprites: Vec<Entry<S>>,
+	/// Back to front.
+	effects: Vec<Entry<E>>,
+	next_id: u64,
+	index: Mutex<Option<Arc<SceneIndex>>>,
+	damage: Damage,
+	_phantom: PhantomData<P>,
+}
+
+struct SceneIndex {
+	all_lines_range: Option<Range<isize>>,
+	sprites: LineIndex,
+	effects: LineIndex,
+}
+
+struct Entry<T: ?Sized> {
+	id: u64,
+	position: Position,
+	z: i32,
+	drawable: Box<T>,
+	/// Where this was last rendered by [`Scene::render_damage`].
+	bounds: Option<Bounds>,
+	/// Whether this changed since then.
+	dirty: bool,
+}
+
+impl<P: PixelFormat, S: ?Sized, E: ?Sized> Default for Scene<P, S, E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized, E: ?Sized> Scene<P, S, E> {
+	/// Creates a new empty [`Scene`] instance.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			sprites: Vec::new(),
+			effects: Vec::new(),
+			next_id: 0,
+			index: Mutex::default(),
+			damage: Damage::default(),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Discards the line index after changes.
+	fn invalidate(&mut self) {
+		*self.index.get_mut().unwrap_or_else(PoisonError::into_inner) = None;
+	}
+
+	fn next_id(&mut self) -> u64 {
+		self.invalidate();
+		let id = self.next_id;
+		self.next_id += 1;
+		id
+	}
+
+	/// Adds `sprite` at `position` with depth `z`.
+	pub fn insert_sprite(&mut self, sprite: Box<S>, position: Position, z: i32) -> SpriteHandle {
+		let id = self.next_id();
+		// Front to back, so in front of everything with the same `z`.
+		let index = self.sprites.partition_point(|entry| entry.z > z);
+		self.sprites.insert(
+			index,
+			Entry {
+				id,
+				position,
+				z,
+				drawable: sprite,
+				bounds: None,
+				dirty: true,
+			},
+		);
+		SpriteHandle(id)
+	}
+
+	/// Adds `effect` at `position` with depth `z`.
+	pub fn insert_effect(&mut self, effect: Box<E>, position: Position, z: i32) -> EffectHandle {
+		let id = self.next_id();
+		// Back to front, so in front of everything with the same `z`.
+		let index = self.effects.partition_point(|entry| entry.z <= z);
+		self.effects.insert(
+			index,
+			Entry {
+				id,
+				position,
+				z,
+				drawable: effect,
+				bounds: None,
+				dirty: true,
+			},
+		);
+		EffectHandle(id)
+	}
+
+	fn sprite_index(&self, handle: SpriteHandle) -> Option<usize> {
+		self.sprites.iter().position(|entry| entry.id == handle.0)
+	}
+
+	fn effect_index(&self, handle: EffectHandle) -> Option<usize> {
+		self.effects.iter().position(|entry| entry.id == handle.0)
+	}
+
+	/// Removes a sprite, returning it if it was present.
+	pub fn remove_sprite(&mut self, handle: SpriteHandle) -> Option<Box<S>> {
+		self.invalidate();
+		let index = self.sprite_index(handle)?;
+		let entry = self.sprites.remove(index);
+		self.damage.removed.extend(entry.bounds);
+		Some(entry.drawable)
+	}
+
+	/// Removes an effect, returning it if it was present.
+	pub fn remove_effect(&mut self, handle: EffectHandle) -> Option<Box<E>> {
+		self.invalidate();
+		let index = self.effect_index(handle)?;
+		let entry = self.effects.remove(index);
+		self.damage.removed.extend(entry.bounds);
+		Some(entry.drawable)
+	}
+
+	/// Gets a sprite.
+	#[must_use]
+	pub fn sprite(&self, handle: SpriteHandle) -> Option<&S> {
+		let index = self.sprite_index(handle)?;
+		Some(&self.sprites[index].drawable)
+	}
+
+	/// Gets an effect.
+	#[must_use]
+	pub fn effect(&self, handle: EffectHandle) -> Option<&E> {
+		let index = self.effect_index(handle)?;
+		Some(&self.effects[index].drawable)
+	}
+
+	/// Gets a sprite for modification.
+	pub fn sprite_mut(&mut self, handle: SpriteHandle) -> Option<&mut S> {
+		self.invalidate();
+		let index = self.sprite_index(handle)?;
+		let entry = &mut self.sprites[index];
+		entry.dirty = true;
+		Some(&mut entry.drawable)
+	}
+
+	/// Gets an effect for modification.
+	pub fn effect_mut(&mut self, handle: EffectHandle) -> Option<&mut E> {
+		self.invalidate();
+		let index = self.effect_index(handle)?;
+		let entry = &mut self.effects[index];
+		entry.dirty = true;
+		Some(&mut entry.drawable)
+	}
+
+	/// Gets a sprite's position.
+	#[must_use]
+	pub fn sprite_position(&self, handle: SpriteHandle) -> Option<Position> {
+		let index = self.sprite_index(handle)?;
+		Some(self.sprites[index].position)
+	}
+
+	/// Gets an effect's position.
+	#[must_use]
+	pub fn effect_position(&self, handle: EffectHandle) -> Option<Position> {
+		let index = self.effect_index(handle)?;
+		Some(self.effects[index].position)
+	}
+
+	/// Moves a sprite to `position`, returning its previous position if it was present.
+	pub fn move_sprite(&mut self, handle: SpriteHandle, position: Position) -> Option<Position> {
+		self.invalidate();
+		let index = self.sprite_index(handle)?;
+		let entry = &mut self.sprites[index];
+		entry.dirty = true;
+		Some(mem::replace(&mut entry.position, position))
+	}
+
+	/// Moves an effect to `position`, returning its previous position if it was present.
+	pub fn move_effect(&mut self, handle: EffectHandle, position: Position) -> Option<Position> {
+		self.invalidate();
+		let index = self.effect_index(handle)?;
+		let entry = &mut self.effects[index];
+		entry.dirty = true;
+		Some(mem::replace(&mut entry.position, position))
+	}
+
+	/// Changes a sprite's depth to `z`, in front of others with the same `z`, returning its previous depth if it was present.
+	pub fn set_sprite_z(&mut self, handle: SpriteHandle, z: i32) -> Option<i32> {
+		self.invalidate();
+		let index = self.sprite_index(handle)?;
+		let entry = self.sprites.remove(index);
+		let previous = entry.z;
+		let index = self.sprites.partition_point(|entry| entry.z > z);
+		self.sprites.insert(
+			index,
+			Entry {
+				z,
+				dirty: true,
+				..entry
+			},
+		);
+		Some(previous)
+	}
+
+	/// Changes an effect's depth to `z`, in front of others with the same `z`, returning its previous depth if it was present.
+	pub fn set_effect_z(&mut self, handle: EffectHandle, z: i32) -> Option<i32> {
+		self.invalidate();
+		let index = self.effect_index(handle)?;
+		let entry = self.effects.remove(index);
+		let previous = entry.z;
+		let index = self.effects.partition_point(|entry| entry.z <= z);
+		self.effects.insert(
+			index,
+			Entry {
+				z,
+				dirty: true,
+				..entry
+			},
+		);
+		Some(previous)
+	}
+
+	/// Gets the number of sprites.
+	#[must_use]
+	pub fn sprite_count(&self) -> usize {
+		self.sprites.len()
+	}
+
+	/// Gets the number of effects.
+	#[must_use]
+	pub fn effect_count(&self) -> usize {
+		self.effects.len()
+	}
+}
+
+impl<P: PixelFormat, S: ?Sized + Sprite<P>, E: ?Sized + Effect<P>> Scene<P, S, E> {
+	/// Renders an entire line into `buffer`, which holds the pixels from `x = 0` onwards.
+	///
+	/// # Panics
+	///
+	/// - Iff [`P::PIXEL_STRIDE_BITS`](`PixelFormat::PIXEL_STRIDE_BITS`) isn't a multiple of 8,
+	/// - and also in cases where [`render_segment`] would panic.
+	pub fn render_line(&self, line: isize, buffer: &mut [u8]) {
+		assert_eq!(P::PIXEL_STRIDE_BITS % 8, 0);
+
+		let line_span = 0..(buffer.len() / (P::PIXEL_STRIDE_BITS / 8))
+			.try_into()
+			.expect("`buffer.len() / P::PIXEL_STRIDE_BITS` too large");
+		self.render_segment(&None, line, line_span.clone(), line_span, buffer)
+	}
+
+	/// Renders a segment of a line, like [`render_segment`].
+	///
+	/// # Panics
+	///
+	/// In cases where [`render_segment`] would panic.
+	pub fn render_segment(
+		&self,
+		all_lines_range: &Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment_span: Range<isize>,
+		buffer: &mut [u8],
+	) {
+		let index = self.index(all_lines_range.as_ref());
+		render_segment(
+			all_lines_range,
+			line,
+			line_span,
+			segment_span,
+			buffer,
+			index.sprites.on_line(line).into_iter().map(|i| {
+				let entry = &self.sprites[i];
+				(entry.position, &*entry.drawable)
+			}),
+			index.effects.on_line(line).into_iter().map(|i| {
+				let entry = &self.effects[i];
+				(entry.position, &*entry.drawable)
+			}),
+		)
+	}
+
+	/// Updates `canvas` to show this scene, re-rendering only where drawables were added, removed, moved or
+	/// (potentially) modified since the last call.
+	///
+	/// The first call, and any call with a canvas of a different size than before, renders everything.
+	/// Between calls, `canvas` must not be changed or used with another scene.
+	///
+	/// # Panics
+	///
+	/// In cases where [`render_segment`] would panic.
+	pub fn render_damage(&mut self, canvas: &mut Canvas<P>) {
+		let size = (canvas.width(), canvas.height());
+		let all_lines_range: Range<isize> = 0..size.1.try_into().expect("infallible");
+		let line_span: Range<isize> = 0..size.0.try_into().expect("infallible");
+
+		let full = self.damage.size != Some(size);
+		let mut damage = mem::take(&mut self.damage.removed);
+		macro_rules! update {
+			($entries:expr) => {
+				for entry in &mut $entries {
+					if entry.dirty || full {
+						let bounds = damage::bounds(
+							&all_lines_range,
+							&line_span,
+							entry.position,
+							|all_lines_range| entry.drawable.lines(all_lines_range),
+							|all_lines_range, line, line_span| {
+								entry
+									.drawable
+									.line_segment(all_lines_range, line, line_span)
+							},
+						);
+						damage.extend(mem::replace(&mut entry.bounds, bounds.clone()));
+						damage.extend(bounds);
+						entry.dirty = false;
+					}
+				}
+			};
+		}
+		update!(self.sprites);
+		update!(self.effects);
+		self.damage.size = Some(size);
+
+		if full {
+			canvas.render(self);
+			return;
+		}
+		for line in all_lines_range {
+			for columns in damage::damaged_columns(line, &damage) {
+				let line: usize = line.try_into().expect("infallible");
+				canvas.render_rect(self, line..line + 1, columns)
+			}
+		}
+	}
+
+	fn index(&self, all_lines_range: Option<&Range<isize>>) -> Arc<SceneIndex> {
+		let mut index = self.index.lock().unwrap_or_else(PoisonError::into_inner);
+		match &*index {
+			Some(index) if index.all_lines_range.as_ref() == all_lines_range => Arc::clone(index),
+			_ => {
+				let rebuilt = Arc::new(SceneIndex {
+					all_lines_range: all_lines_range.cloned(),
+					sprites: LineIndex::new(
+						self.sprites
+							.iter()
+							.map(|entry| {
+								frame_lines(all_lines_range, entry.position, |all_lines_range| {
+									entry.drawable.lines(all_lines_range)
+								})
+							})
+							.collect(),
+					),
+					effects: LineIndex::new(
+						self.effects
+							.iter()
+							.map(|entry| {
+								frame_lines(all_lines_range, entry.position, |all_lines_range| {
+									entry.drawable.lines(all_lines_range)
+								})
+							})
+							.collect(),
+					),
+				});
+				*index = Some(Arc::clone(&rebuilt));
+				rebuilt
+			}
+		}
+	}
+}
+
+/// Gets the lines of a drawable at `position`, in frame coordinates.
+fn frame_lines(
+	all_lines_range: Option<&Range<isize>>,
+	position: Position,
+	lines: impl FnOnce(Option<Range<isize>>) -> Range<isize>,
+) -> Range<isize> {
+	let lines = lines(all_lines_range.map(|all_lines_range| {
+		all_lines_range.start - position.y..all_lines_range.end - position.y
+	}));
+	lines.start.saturating_add(position.y)..lines.end.saturating_add(position.y)
+}
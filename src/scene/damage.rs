@@ -0,0 +1,89 @@
+use crate::{Intersect, Position};
+use std::{
+	cmp::{max, min},
+	convert::TryInto,
+	ops::Range,
+};
+
+/// A rectangle of a canvas that a drawable was rendered into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Bounds {
+	pub(super) lines: Range<isize>,
+	pub(super) columns: Range<isize>,
+}
+
+/// Changes to a scene since it was last rendered into a canvas with [`Scene::render_damage`](`super::Scene::render_damage`).
+#[derive(Debug, Default)]
+pub(super) struct Damage {
+	/// The width and height of that canvas.
+	pub(super) size: Option<(usize, usize)>,
+	/// Areas of removed drawables.
+	pub(super) removed: Vec<Bounds>,
+}
+
+/// Gets the bounds of a drawable at `position` within a canvas with `all_lines_range` and `line_span`,
+/// if it is visible there at all.
+pub(super) fn bounds(
+	all_lines_range: &Range<isize>,
+	line_span: &Range<isize>,
+	position: Position,
+	lines: impl FnOnce(Option<Range<isize>>) -> Range<isize>,
+	line_segment: impl Fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>,
+) -> Option<Bounds> {
+	let relative_lines_range = all_lines_range.start - position.y..all_lines_range.end - position.y;
+	let lines = super::frame_lines(Some(all_lines_range), position, lines)
+		.intersect(all_lines_range.clone())?;
+
+	let mut bounds: Option<Bounds> = None;
+	for line in lines {
+		if let Some(columns) = line_segment(
+			Some(relative_lines_range.clone()),
+			line - position.y,
+			line_span.start - position.x..line_span.end - position.x,
+		)
+		.intersect(line_span.start - position.x..line_span.end - position.x)
+		{
+			let columns = columns.start + position.x..columns.end + position.x;
+			bounds = Some(match bounds {
+				Some(bounds) => Bounds {
+					lines: bounds.lines.start..line + 1,
+					columns: min(bounds.columns.start, columns.start)
+						..max(bounds.columns.end, columns.end),
+				},
+				None => Bounds {
+					lines: line..line + 1,
+					columns,
+				},
+			});
+		}
+	}
+	bounds
+}
+
+/// Gets the disjoint column ranges damaged by `bounds` on `line`, in ascending order.
+pub(super) fn damaged_columns<'a>(
+	line: isize,
+	bounds: impl IntoIterator<Item = &'a Bounds>,
+) -> Vec<Range<usize>> {
+	let mut columns: Vec<Range<isize>> = bounds
+		.into_iter()
+		.filter(|bounds| bounds.lines.contains(&line))
+		.map(|bounds| bounds.columns.clone())
+		.collect();
+	columns.sort_by_key(|columns| columns.start);
+
+	let mut merged: Vec<Range<isize>> = Vec::with_capacity(columns.len());
+	for columns in columns {
+		match merged.last_mut() {
+			Some(last) if columns.start <= last.end => last.end = max(last.end, columns.end),
+			_ => merged.push(columns),
+		}
+	}
+	merged
+		.into_iter()
+		.map(|columns| {
+			columns.start.try_into().expect("infallible")
+				..columns.end.try_into().expect("infallible")
+		})
+		.collect()
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashMap, convert::TryFrom, ops::Range};
+
+/// Lines per bucket.
+const BUCKET_HEIGHT: isize = 16;
+
+/// Drawables spanning more buckets than this are checked on every line instead.
+const MAX_BUCKETS: usize = 1024;
+
+/// Line ranges of a list of drawables, bucketed so that those on a particular line can be found quickly.
+#[derive(Debug)]
+pub(super) struct LineIndex {
+	lines: Vec<Range<isize>>,
+	/// Indices into `lines`, in ascending order.
+	buckets: HashMap<isize, Vec<usize>>,
+	/// Indices into `lines`, in ascending order.
+	unbucketed: Vec<usize>,
+}
+impl LineIndex {
+	pub(super) fn new(lines: Vec<Range<isize>>) -> Self {
+		let mut buckets: HashMap<isize, Vec<usize>> = HashMap::new();
+		let mut unbucketed = Vec::new();
+		for (i, lines) in lines.iter().enumerate() {
+			if lines.is_empty() {
+				continue;
+			}
+
+			let first = lines.start.div_euclid(BUCKET_HEIGHT);
+			let last = (lines.end - 1).div_euclid(BUCKET_HEIGHT);
+			match last
+				.checked_sub(first)
+				.and_then(|span| usize::try_from(span).ok())
+			{
+				Some(span) if span < MAX_BUCKETS => {
+					for bucket in first..=last {
+						buckets.entry(bucket).or_default().push(i);
+					}
+				}
+				_ => unbucketed.push(i),
+			}
+		}
+		Self {
+			lines,
+			buckets,
+			unbucketed,
+		}
+	}
+
+	/// Gets the indices of all drawables on `line`, in ascending order.
+	pub(super) fn on_line(&self, line: isize) -> Vec<usize> {
+		let bucketed = self
+			.buckets
+			.get(&line.div_euclid(BUCKET_HEIGHT))
+			.map_or(&[][..], Vec::as_slice);
+
+		let mut on_line = Vec::with_capacity(bucketed.len() + self.unbucketed.len());
+		let (mut bucketed, mut unbucketed) = (
+			bucketed.iter().peekable(),
+			self.unbucketed.iter().peekable(),
+		);
+		loop {
+			let next = match (bucketed.peek(), unbucketed.peek()) {
+				(Some(a), Some(b)) if a < b => bucketed.next(),
+				(Some(_) | None, Some(_)) => unbucketed.next(),
+				(Some(_), None) => bucketed.next(),
+				(None, None) => break,
+			};
+			let i = *next.expect("infallible");
+			if self.lines[i].contains(&line) {
+				on_line.push(i);
+			}
+		}
+		on_line
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{LineIndex, BUCKET_HEIGHT, MAX_BUCKETS};
+	use std::ops::Range;
+
+	fn buckets(index: &LineIndex, i: usize) -> Vec<isize> {
+		let mut buckets: Vec<isize> = index
+			.buckets
+			.iter()
+			.filter(|(_, indices)| indices.contains(&i))
+			.map(|(bucket, _)| *bucket)
+			.collect();
+		buckets.sort_unstable();
+		buckets
+	}
+
+	/// Finds the drawables on `line` by checking each one.
+	fn on_line(lines: &[Range<isize>], line: isize) -> Vec<usize> {
+		(0..lines.len())
+			.filter(|i| lines[*i].contains(&line))
+			.collect()
+	}
+
+	#[test]
+	fn bucket_edges() {
+		let lines = vec![0..16, 15..17, 16..32, 16..33, 31..32, 5..5];
+		let index = LineIndex::new(lines.clone());
+		assert_eq!(buckets(&index, 0), [0]);
+		assert_eq!(buckets(&index, 1), [0, 1]);
+		assert_eq!(buckets(&index, 2), [1]);
+		assert_eq!(buckets(&index, 3), [1, 2]);
+		assert_eq!(buckets(&index, 4), [1]);
+		// Empty ranges are dropped.
+		assert_eq!(buckets(&index, 5), []);
+		assert!(index.unbucketed.is_empty());
+
+		for line in -2..50 {
+			assert_eq!((line, index.on_line(line)), (line, on_line(&lines, line)));
+		}
+	}
+
+	#[test]
+	fn negative_lines() {
+		let lines = vec![-1..0, -16..-15, -17..-16, -17..1, -33..-32];
+		let index = LineIndex::new(lines.clone());
+		assert_eq!(buckets(&index, 0), [-1]);
+		assert_eq!(buckets(&index, 1), [-1]);
+		assert_eq!(buckets(&index, 2), [-2]);
+		assert_eq!(buckets(&index, 3), [-2, -1, 0]);
+		assert_eq!(buckets(&index, 4), [-3]);
+
+		for line in -40..5 {
+			assert_eq!((line, index.on_line(line)), (line, on_line(&lines, line)));
+		}
+	}
+
+	#[test]
+	fn unbucketed() {
+		#[allow(clippy::cast_possible_wrap)]
+		let limit = BUCKET_HEIGHT * MAX_BUCKETS as isize;
+		let lines = vec![
+			0..1,
+			0..limit,
+			0..limit + 1,
+			-1..limit,
+			isize::MIN..isize::MAX,
+			20..21,
+			limit..limit + 1,
+		];
+		let index = LineIndex::new(lines.clone());
+		assert_eq!(buckets(&index, 1).len(), MAX_BUCKETS);
+		assert_eq!(index.unbucketed, [2, 3, 4]);
+
+		// Both kinds are merged in order.
+		for line in [-1, 0, 20, 21, limit - 1, limit, isize::MIN, isize::MAX - 1] {
+			assert_eq!((line, index.on_line(line)), (line, on_line(&lines, line)));
+		}
+	}
+}
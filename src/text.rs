@@ -0,0 +1,29 @@
+//! Fonts for the text drawables.
+
+use std::{error::Error, fmt};
+
+mod bitmap_font;
+mod glyph_cache;
+mod outline_font;
+pub use bitmap_font::BitmapFont;
+pub(crate) use bitmap_font::BitmapGlyph;
+pub use glyph_cache::GlyphCache;
+pub use outline_font::OutlineFont;
+pub(crate) use outline_font::OUTLINE_TOLERANCE;
+
+/// An error encountered while loading a font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseFontError {
+	message: &'static str,
+}
+impl ParseFontError {
+	pub(crate) fn new(message: &'static str) -> Self {
+		Self { message }
+	}
+}
+impl fmt::Display for ParseFontError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid font data: {}", self.message)
+	}
+}
+impl Error for ParseFontError {}
@@ -0,0 +1,412 @@
+use super::ParseFontError;
+use std::{collections::HashMap, convert::TryInto, str};
+
+/// A fixed-size bitmap font, loaded from [BDF](https://en.wikipedia.org/wiki/Glyph_Bitmap_Distribution_Format)
+/// or [PSF](https://en.wikipedia.org/wiki/PC_Screen_Font) data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitmapFont {
+	glyphs: HashMap<char, BitmapGlyph>,
+	fallback: Option<BitmapGlyph>,
+	ascent: isize,
+	line_height: isize,
+}
+
+/// A glyph's coverage bitmap and metrics, relative to the pen position.
+///
+/// That's on the line's top edge for [`BitmapFont`]s and on the baseline for rasterized outline glyphs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BitmapGlyph {
+	pub(crate) left: isize,
+	pub(crate) top: isize,
+	pub(crate) width: usize,
+	pub(crate) advance: isize,
+	/// Row-major coverage, either `0` or [`u8::MAX`] for [`BitmapFont`]s.
+	pub(crate) coverage: Vec<u8>,
+}
+impl BitmapGlyph {
+	pub(crate) fn height(&self) -> usize {
+		self.coverage.len().checked_div(self.width).unwrap_or(0)
+	}
+}
+
+impl BitmapFont {
+	/// Loads a font in the Glyph Bitmap Distribution Format.
+	///
+	/// Characters are looked up by their `ENCODING`, which is assumed to be Unicode.
+	/// The `DEFAULT_CHAR` (or else U+FFFD or `?`) is drawn for missing characters.
+	///
+	/// # Errors
+	///
+	/// Iff `data` isn't a valid BDF font.
+	pub fn from_bdf(data: &[u8]) -> Result<Self, ParseFontError> {
+		let data = str::from_utf8(data).map_err(|_| ParseFontError::new("BDF is not UTF-8"))?;
+		let mut lines = data.lines().map(str::trim);
+
+		match lines.next() {
+			Some(line) if line.starts_with("STARTFONT") => (),
+			_ => return Err(ParseFontError::new("missing STARTFONT")),
+		}
+
+		let mut bounding_box = None;
+		let mut ascent = None;
+		let mut descent = None;
+		let mut default_char = None;
+		let mut glyphs = HashMap::new();
+
+		while let Some(line) = lines.next() {
+			let mut words = line.split_ascii_whitespace();
+			match words.next() {
+				Some("FONTBOUNDINGBOX") => bounding_box = Some(bdf_numbers::<_, 4>(words)?),
+				Some("FONT_ASCENT") => ascent = Some(bdf_numbers::<_, 1>(words)?[0]),
+				Some("FONT_DESCENT") => descent = Some(bdf_numbers::<_, 1>(words)?[0]),
+				Some("DEFAULT_CHAR") => default_char = Some(bdf_numbers::<_, 1>(words)?[0]),
+				Some("STARTCHAR") => {
+					let (encoding, glyph) = bdf_glyph(&mut lines)?;
+					glyphs.insert(encoding, glyph);
+				}
+				Some("ENDFONT") => break,
+				_ => (),
+			}
+		}
+
+		let [_, height, _, y_offset] =
+			bounding_box.ok_or_else(|| ParseFontError::new("missing FONTBOUNDINGBOX"))?;
+		let out_of_range = || ParseFontError::new("font metrics out of range");
+		let ascent = ascent
+			.or_else(|| height.checked_add(y_offset))
+			.ok_or_else(out_of_range)?;
+		let descent = descent
+			.or_else(|| y_offset.checked_neg())
+			.ok_or_else(out_of_range)?;
+
+		// BDF glyphs are positioned relative to the baseline, upwards.
+		let glyphs: HashMap<char, BitmapGlyph> = glyphs
+			.into_iter()
+			.filter_map(|(encoding, glyph): (isize, BitmapGlyph)| {
+				let character = encoding.try_into().ok().and_then(char::from_u32)?;
+				Some(
+					ascent
+						.checked_sub(glyph.top)
+						.map(|top| (character, BitmapGlyph { top, ..glyph }))
+						.ok_or_else(|| ParseFontError::new("glyph position out of range")),
+				)
+			})
+			.collect::<Result<_, _>>()?;
+
+		let fallback = default_char
+			.and_then(|default_char| default_char.try_into().ok().and_then(char::from_u32))
+			.into_iter()
+			.chain(['\u{FFFD}', '?'].iter().copied())
+			.find_map(|character| glyphs.get(&character))
+			.cloned();
+
+		Ok(Self {
+			glyphs,
+			fallback,
+			ascent,
+			line_height: ascent.checked_add(descent).ok_or_else(out_of_range)?,
+		})
+	}
+
+	/// Loads a PC Screen Font, version 1 or 2.
+	///
+	/// Without Unicode table, glyph indices are used as code points.
+	/// U+FFFD or `?` is drawn for missing characters, if available.
+	///
+	/// # Errors
+	///
+	/// Iff `data` isn't a valid PSF font.
+	///
+	/// # Panics
+	///
+	/// Iff the glyph size doesn't fit into [`isize`].
+	pub fn from_psf(data: &[u8]) -> Result<Self, ParseFontError> {
+		let truncated = || ParseFontError::new("PSF data is truncated");
+
+		let (glyph_count, glyph_size, width, height, glyphs_start, unicode_table): (
+			usize,
+			usize,
+			usize,
+			usize,
+			usize,
+			Option<PsfTable>,
+		) = match data {
+			[0x36, 0x04, mode, char_size, ..] => (
+				if mode & 0x01 == 0 { 256 } else { 512 },
+				usize::from(*char_size),
+				8,
+				usize::from(*char_size),
+				4,
+				if mode & 0x06 == 0 {
+					None
+				} else {
+					Some(PsfTable::Ucs2)
+				},
+			),
+			[0x72, 0xb5, 0x4a, 0x86, ..] => {
+				let header = |index: usize| -> Result<usize, ParseFontError> {
+					let bytes = data.get(index * 4..index * 4 + 4).ok_or_else(truncated)?;
+					u32::from_le_bytes(bytes.try_into().expect("infallible"))
+						.try_into()
+						.map_err(|_| ParseFontError::new("PSF header value too large"))
+				};
+				(
+					header(4)?,
+					header(5)?,
+					header(7)?,
+					header(6)?,
+					header(2)?,
+					if header(3)? & 0x01 == 0 {
+						None
+					} else {
+						Some(PsfTable::Utf8)
+					},
+				)
+			}
+			_ => return Err(ParseFontError::new("unrecognised PSF magic")),
+		};
+
+		if width == 0 || height == 0 {
+			return Err(ParseFontError::new("PSF glyphs are empty"));
+		}
+		#[allow(clippy::manual_div_ceil)]
+		let row_size = (width + 7) / 8;
+		// This also rejects a glyph size of zero, which `chunks_exact` doesn't allow.
+		if glyph_size / row_size < height {
+			return Err(ParseFontError::new("PSF glyphs are too small"));
+		}
+		let glyphs_end = glyph_count
+			.checked_mul(glyph_size)
+			.and_then(|size| size.checked_add(glyphs_start))
+			.ok_or_else(truncated)?;
+		let glyph_data = data.get(glyphs_start..glyphs_end).ok_or_else(truncated)?;
+
+		let bitmaps: Vec<BitmapGlyph> = glyph_data
+			.chunks_exact(glyph_size)
+			.map(|bits| psf_glyph(bits, width, height, row_size))
+			.collect();
+
+		let mut glyphs = HashMap::new();
+		match unicode_table {
+			None => {
+				for (index, glyph) in bitmaps.iter().enumerate() {
+					if let Some(character) = index.try_into().ok().and_then(char::from_u32) {
+						glyphs.insert(character, glyph.clone());
+					}
+				}
+			}
+			Some(table) => {
+				let mut entries = &data[glyphs_end..];
+				for glyph in &bitmaps {
+					for character in table.entry(&mut entries)? {
+						glyphs.insert(character, glyph.clone());
+					}
+				}
+			}
+		}
+
+		let fallback = ['\u{FFFD}', '?']
+			.iter()
+			.find_map(|character| glyphs.get(character))
+			.cloned();
+
+		let height = height.try_into().expect("PSF glyph too tall");
+		Ok(Self {
+			glyphs,
+			fallback,
+			ascent: height,
+			line_height: height,
+		})
+	}
+
+	/// Gets the distance between baselines of consecutive lines, in pixels.
+	#[must_use]
+	pub fn line_height(&self) -> isize {
+		self.line_height
+	}
+
+	/// Gets the distance from the top of a line to its baseline, in pixels.
+	#[must_use]
+	pub fn ascent(&self) -> isize {
+		self.ascent
+	}
+
+	/// Gets the horizontal advance of `character`, in pixels.
+	#[must_use]
+	pub fn advance(&self, character: char) -> isize {
+		self.glyph(character).map_or(0, |glyph| glyph.advance)
+	}
+
+	pub(crate) fn glyph(&self, character: char) -> Option<&BitmapGlyph> {
+		self.glyphs.get(&character).or(self.fallback.as_ref())
+	}
+}
+
+/// Decodes a PSF glyph bitmap with `row_size` bytes per row.
+fn psf_glyph(bits: &[u8], width: usize, height: usize, row_size: usize) -> BitmapGlyph {
+	BitmapGlyph {
+		left: 0,
+		top: 0,
+		width,
+		advance: width.try_into().expect("PSF glyph too wide"),
+		coverage: (0..height)
+			.flat_map(|y| (0..width).map(move |x| (y, x)))
+			.map(|(y, x)| {
+				if bits[y * row_size + x / 8] & (0x80 >> (x % 8)) == 0 {
+					0
+				} else {
+					u8::MAX
+				}
+			})
+			.collect(),
+	}
+}
+
+fn bdf_numbers<'a, I: Iterator<Item = &'a str>, const N: usize>(
+	mut words: I,
+) -> Result<[isize; N], ParseFontError> {
+	let mut numbers = [0; N];
+	for number in &mut numbers {
+		*number = words
+			.next()
+			.and_then(|word| word.parse().ok())
+			.ok_or_else(|| ParseFontError::new("expected BDF number"))?;
+	}
+	Ok(numbers)
+}
+
+/// Parses a BDF glyph after `STARTCHAR`, with `top` still relative to the baseline.
+fn bdf_glyph<'a>(
+	lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<(isize, BitmapGlyph), ParseFontError> {
+	let mut encoding = None;
+	let mut advance = None;
+	let mut bounding_box = None;
+
+	for line in &mut *lines {
+		let mut words = line.split_ascii_whitespace();
+		match words.next() {
+			Some("ENCODING") => encoding = Some(bdf_numbers::<_, 1>(words)?[0]),
+			Some("DWIDTH") => advance = Some(bdf_numbers::<_, 1>(words)?[0]),
+			Some("BBX") => bounding_box = Some(bdf_numbers::<_, 4>(words)?),
+			Some("BITMAP") => break,
+			Some("ENDCHAR") => return Err(ParseFontError::new("missing BITMAP")),
+			_ => (),
+		}
+	}
+
+	let [width, height, x_offset, y_offset] =
+		bounding_box.ok_or_else(|| ParseFontError::new("missing BBX"))?;
+	let columns: usize = width
+		.try_into()
+		.map_err(|_| ParseFontError::new("negative BBX width"))?;
+	let rows: usize = height
+		.try_into()
+		.map_err(|_| ParseFontError::new("negative BBX height"))?;
+
+	let size = columns
+		.checked_mul(rows)
+		.ok_or_else(|| ParseFontError::new("BBX is too large"))?;
+	#[allow(clippy::manual_div_ceil)]
+	let row_size = (columns + 7) / 8;
+
+	// Decoded before allocating `coverage`, so its size is backed by actual bitmap rows.
+	let bitmap = lines
+		.take(rows)
+		.map(|row| {
+			row.as_bytes()
+				.chunks(2)
+				.map(|hex| {
+					str::from_utf8(hex)
+						.ok()
+						.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+						.ok_or_else(|| ParseFontError::new("invalid BDF bitmap row"))
+				})
+				.collect::<Result<Vec<u8>, _>>()
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	if bitmap.len() < rows {
+		return Err(ParseFontError::new("BDF bitmap is truncated"));
+	}
+	if bitmap.iter().any(|bits| bits.len() < row_size) {
+		return Err(ParseFontError::new("BDF bitmap row too short"));
+	}
+
+	let mut coverage = Vec::with_capacity(size);
+	for bits in &bitmap {
+		coverage.extend((0..columns).map(|x| {
+			if bits[x / 8] & (0x80 >> (x % 8)) == 0 {
+				0
+			} else {
+				u8::MAX
+			}
+		}));
+	}
+
+	if !lines.any(|line| line == "ENDCHAR") {
+		return Err(ParseFontError::new("missing ENDCHAR"));
+	}
+
+	Ok((
+		encoding.ok_or_else(|| ParseFontError::new("missing ENCODING"))?,
+		BitmapGlyph {
+			left: x_offset,
+			// Still relative to the baseline, upwards.
+			top: y_offset
+				.checked_add(height)
+				.ok_or_else(|| ParseFontError::new("BBX out of range"))?,
+			width: columns,
+			advance: advance.unwrap_or(0),
+			coverage,
+		},
+	))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PsfTable {
+	Ucs2,
+	Utf8,
+}
+impl PsfTable {
+	/// Reads one glyph's entry from the Unicode table, skipping combining sequences.
+	fn entry(self, entries: &mut &[u8]) -> Result<Vec<char>, ParseFontError> {
+		let truncated = || ParseFontError::new("PSF Unicode table is truncated");
+		let mut characters = Vec::new();
+		let mut in_sequence = false;
+		match self {
+			PsfTable::Ucs2 => loop {
+				let (unit, rest) = match *entries {
+					[low, high, ref rest @ ..] => (u16::from_le_bytes([*low, *high]), rest),
+					_ => return Err(truncated()),
+				};
+				*entries = rest;
+				match unit {
+					0xFFFF => break,
+					0xFFFE => in_sequence = true,
+					unit if !in_sequence => characters.extend(char::from_u32(unit.into())),
+					_ => (),
+				}
+			},
+			PsfTable::Utf8 => loop {
+				let end = entries
+					.iter()
+					.position(|byte| *byte == 0xFF || *byte == 0xFE)
+					.ok_or_else(truncated)?;
+				if !in_sequence {
+					characters.extend(
+						str::from_utf8(&entries[..end])
+							.map_err(|_| ParseFontError::new("invalid UTF-8 in PSF Unicode table"))?
+							.chars(),
+					);
+				}
+				let terminator = entries[end];
+				*entries = &entries[end + 1..];
+				if terminator == 0xFF {
+					break;
+				}
+				in_sequence = true;
+			},
+		}
+		Ok(characters)
+	}
+}
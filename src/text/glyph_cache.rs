@@ -0,0 +1,302 @@
+use super::{outline_font::OUTLINE_TOLERANCE, BitmapGlyph, OutlineFont};
+use crate::raster::Polygons;
+use std::{
+	collections::{BTreeMap, HashMap},
+	mem::size_of,
+	sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// A shared store of rasterized [`OutlineFont`] glyphs, which text drawables can use instead of rasterizing their outlines on every line.
+///
+/// Glyphs are keyed by font, size and glyph id, where fonts are identified by the [`OutlineFont::new`] call that loaded them.
+/// Once the cached coverage exceeds the memory budget, the least recently used glyphs are evicted.
+/// Drawables keep the glyphs they use alive independently of the cache.
+#[derive(Debug)]
+pub struct GlyphCache {
+	budget: usize,
+	entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+	glyphs: HashMap<Key, (Arc<BitmapGlyph>, u64)>,
+	/// Keys by the time they were last used.
+	recency: BTreeMap<u64, Key>,
+	time: u64,
+	usage: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+	font: usize,
+	size: u32,
+	glyph: u16,
+}
+
+impl GlyphCache {
+	/// Creates a new empty [`GlyphCache`] instance that holds on to about `budget` bytes of glyphs at most.
+	#[must_use]
+	pub fn new(budget: usize) -> Self {
+		Self {
+			budget,
+			entries: Mutex::default(),
+		}
+	}
+
+	/// Gets the memory budget, in bytes.
+	#[must_use]
+	pub fn budget(&self) -> usize {
+		self.budget
+	}
+
+	/// Gets the approximate memory used by cached glyphs, in bytes.
+	#[must_use]
+	pub fn usage(&self) -> usize {
+		self.entries().usage
+	}
+
+	/// Gets the number of cached glyphs.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.entries().glyphs.len()
+	}
+
+	/// Checks whether no glyphs are cached.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Evicts all glyphs.
+	pub fn clear(&self) {
+		*self.entries() = Entries::default();
+	}
+
+	/// Gets `glyph` of `font` at `size` pixels per em, rasterizing it on a miss.
+	pub(crate) fn glyph(&self, font: &OutlineFont<'_>, size: f32, glyph: u16) -> Arc<BitmapGlyph> {
+		let key = Key {
+			font: font.id(),
+			size: size.to_bits(),
+			glyph,
+		};
+
+		if let Some(cached) = self.get(key) {
+			return cached;
+		}
+
+		// Rasterize without blocking other users of the cache.
+		self.insert(key, Arc::new(rasterize(font, size, glyph)))
+	}
+
+	/// Gets a cached glyph, marking it as the most recently used.
+	fn get(&self, key: Key) -> Option<Arc<BitmapGlyph>> {
+		let mut entries = self.entries();
+		let entries = &mut *entries;
+		entries.time += 1;
+		let (cached, last_used) = entries.glyphs.get_mut(&key)?;
+		entries.recency.remove(&*last_used);
+		entries.recency.insert(entries.time, key);
+		*last_used = entries.time;
+		Some(Arc::clone(cached))
+	}
+
+	/// Caches `rasterized` as the most recently used glyph, evicting others to stay within budget.
+	///
+	/// Glyphs larger than the whole budget are returned without being cached.
+	fn insert(&self, key: Key, rasterized: Arc<BitmapGlyph>) -> Arc<BitmapGlyph> {
+		let added = cost(&rasterized);
+		if added > self.budget {
+			return rasterized;
+		}
+
+		let mut entries = self.entries();
+		let entries = &mut *entries;
+		while entries.usage + added > self.budget {
+			let oldest = *entries.recency.keys().next().expect("over budget");
+			let evicted = entries.recency.remove(&oldest).expect("infallible");
+			let (evicted, _) = entries.glyphs.remove(&evicted).expect("infallible");
+			entries.usage -= cost(&evicted);
+		}
+		entries.time += 1;
+		if let Some((_, last_used)) = entries
+			.glyphs
+			.insert(key, (Arc::clone(&rasterized), entries.time))
+		{
+			// Another thread rasterized it concurrently.
+			entries.recency.remove(&last_used);
+			entries.usage -= added;
+		}
+		entries.recency.insert(entries.time, key);
+		entries.usage += added;
+		rasterized
+	}
+
+	fn entries(&self) -> MutexGuard<'_, Entries> {
+		// Entries are consistent between statements, so a panic elsewhere doesn't invalidate them.
+		self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+fn cost(glyph: &BitmapGlyph) -> usize {
+	glyph.coverage.len() + size_of::<(Key, BitmapGlyph, u64)>()
+}
+
+/// Rasterizes `glyph` with anti-aliasing, with the pen position on the baseline at a pixel corner.
+fn rasterize(font: &OutlineFont<'_>, size: f32, glyph: u16) -> BitmapGlyph {
+	let advance = f32::from(font.glyph_advance(glyph)) * size / f32::from(font.units_per_em());
+	let path = font.glyph_path(glyph, size);
+	let mut polygons = Polygons::new();
+	for (points, _closed) in path.flatten(OUTLINE_TOLERANCE) {
+		polygons.add_polygon(&points);
+	}
+
+	let rows = polygons.lines();
+	let columns = rows
+		.clone()
+		.map(|line| polygons.line_segment(line))
+		.filter(|segment| !segment.is_empty())
+		.reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+		.unwrap_or(0..0);
+	let coverage = if columns.is_empty() {
+		Vec::new()
+	} else {
+		rows.clone()
+			.flat_map(|line| polygons.coverage(line, columns.clone(), |winding| winding != 0))
+			.collect()
+	};
+
+	BitmapGlyph {
+		left: columns.start,
+		top: if coverage.is_empty() { 0 } else { rows.start },
+		width: columns.len(),
+		#[allow(clippy::cast_possible_truncation)]
+		advance: advance.round() as isize,
+		coverage,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{cost, BitmapGlyph, GlyphCache, Key};
+	use std::sync::Arc;
+
+	fn key(glyph: u16) -> Key {
+		Key {
+			font: 0,
+			size: 0,
+			glyph,
+		}
+	}
+
+	fn bitmap(len: usize) -> Arc<BitmapGlyph> {
+		Arc::new(BitmapGlyph {
+			left: 0,
+			top: 0,
+			width: 1,
+			advance: 1,
+			coverage: vec![0; len],
+		})
+	}
+
+	/// The cost of [`bitmap(100)`](`bitmap`).
+	fn unit() -> usize {
+		cost(&bitmap(100))
+	}
+
+	/// Gets the cached glyph ids, checking that the bookkeeping matches them.
+	fn cached(cache: &GlyphCache) -> Vec<u16> {
+		let entries = cache.entries();
+		assert_eq!(entries.recency.len(), entries.glyphs.len());
+		for (time, key) in &entries.recency {
+			assert_eq!(entries.glyphs[key].1, *time);
+		}
+		assert_eq!(
+			entries.usage,
+			entries
+				.glyphs
+				.values()
+				.map(|(glyph, _)| cost(glyph))
+				.sum::<usize>()
+		);
+		assert!(entries.usage <= cache.budget);
+
+		let mut glyphs: Vec<u16> = entries.glyphs.keys().map(|key| key.glyph).collect();
+		glyphs.sort_unstable();
+		glyphs
+	}
+
+	#[test]
+	fn lru_eviction() {
+		let cache = GlyphCache::new(3 * unit());
+		for glyph in 0..3 {
+			cache.insert(key(glyph), bitmap(100));
+		}
+		assert_eq!(cached(&cache), [0, 1, 2]);
+		assert_eq!(cache.usage(), 3 * unit());
+
+		// Lookups count as use.
+		assert!(cache.get(key(0)).is_some());
+		cache.insert(key(3), bitmap(100));
+		assert_eq!(cached(&cache), [0, 2, 3]);
+		assert!(cache.get(key(1)).is_none());
+
+		cache.insert(key(4), bitmap(100));
+		assert_eq!(cached(&cache), [0, 3, 4]);
+
+		// Larger glyphs evict as many as necessary, oldest first.
+		cache.insert(key(5), bitmap(100 + unit()));
+		assert_eq!(cached(&cache), [4, 5]);
+		assert_eq!(cache.usage(), 3 * unit());
+
+		cache.clear();
+		assert_eq!(cached(&cache), []);
+		assert_eq!(cache.usage(), 0);
+	}
+
+	#[test]
+	fn budget() {
+		let cache = GlyphCache::new(2 * unit());
+		cache.insert(key(0), bitmap(100));
+		cache.insert(key(1), bitmap(100));
+
+		// Glyphs larger than the budget are returned, but not cached and don't evict anything.
+		let large = bitmap(100 + 2 * unit());
+		assert!(Arc::ptr_eq(
+			&cache.insert(key(2), Arc::clone(&large)),
+			&large
+		));
+		assert_eq!(cached(&cache), [0, 1]);
+		assert!(cache.get(key(2)).is_none());
+
+		// A glyph that uses the whole budget is cached alone.
+		cache.insert(key(3), bitmap(100 + unit()));
+		assert_eq!(cached(&cache), [3]);
+		assert_eq!(cache.usage(), cache.budget());
+
+		let empty = GlyphCache::new(0);
+		empty.insert(key(0), bitmap(0));
+		assert!(empty.is_empty());
+	}
+
+	#[test]
+	fn concurrent_insert() {
+		let cache = GlyphCache::new(3 * unit());
+		cache.insert(key(0), bitmap(100));
+		cache.insert(key(1), bitmap(100));
+
+		// Another thread finished rasterizing the same glyph first.
+		let replacement = bitmap(100);
+		cache.insert(key(0), Arc::clone(&replacement));
+		assert_eq!(cached(&cache), [0, 1]);
+		assert_eq!(cache.usage(), 2 * unit());
+		assert!(Arc::ptr_eq(&cache.get(key(0)).unwrap(), &replacement));
+
+		// The replaced entry's use doesn't linger.
+		cache.insert(key(2), bitmap(100));
+		cache.insert(key(3), bitmap(100));
+		assert_eq!(cached(&cache), [0, 2, 3]);
+		cache.insert(key(4), bitmap(100));
+		cache.insert(key(5), bitmap(100));
+		assert_eq!(cached(&cache), [3, 4, 5]);
+	}
+}
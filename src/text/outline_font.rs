@@ -0,0 +1,506 @@
+use super::ParseFontError;
+use crate::geometry::{Path, Point, Transform};
+use std::{
+	convert::TryInto,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// How far flattened glyph outlines may deviate from their curves, in pixels.
+pub(crate) const OUTLINE_TOLERANCE: f32 = 0.1;
+
+/// The most components a composite glyph may have, counted across all nesting levels.
+const MAX_COMPONENTS: usize = 1024;
+
+/// The [`OutlineFont::id`] of the next font to be loaded.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A scalable font with TrueType (`glyf`) outlines, from TrueType or OpenType data.
+///
+/// OpenType fonts with CFF outlines aren't supported. Of font collections, the first font is used.
+///
+/// Each call to [`OutlineFont::new`] loads a distinct font, which is only equal to its copies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutlineFont<'a> {
+	id: usize,
+	data: &'a [u8],
+	cmap: &'a [u8],
+	glyf: &'a [u8],
+	hmtx: &'a [u8],
+	loca: &'a [u8],
+	long_loca: bool,
+	glyph_count: u16,
+	horizontal_metric_count: u16,
+	units_per_em: u16,
+	ascent: i16,
+	descent: i16,
+	line_gap: i16,
+}
+
+impl<'a> OutlineFont<'a> {
+	/// Loads a font from TrueType or OpenType data.
+	///
+	/// Glyphs are only parsed as needed. Malformed glyphs are drawn empty.
+	///
+	/// # Errors
+	///
+	/// Iff `data` doesn't contain the necessary tables for TrueType outlines.
+	pub fn new(data: &'a [u8]) -> Result<Self, ParseFontError> {
+		let invalid = || ParseFontError::new("truncated or invalid font tables");
+
+		let offset = match data.get(..4) {
+			Some(b"ttcf") => u32_at(data, 12).ok_or_else(invalid)?,
+			Some(b"OTTO") => return Err(ParseFontError::new("CFF outlines are not supported")),
+			Some([0, 1, 0, 0] | b"true") => 0,
+			_ => return Err(ParseFontError::new("unrecognised font format")),
+		};
+		let offset: usize = offset.try_into().map_err(|_| invalid())?;
+		let table_count = u16_at(data, offset + 4).ok_or_else(invalid)?;
+
+		let table = |tag: &[u8; 4]| -> Result<&'a [u8], ParseFontError> {
+			for i in 0..usize::from(table_count) {
+				let record = offset + 12 + i * 16;
+				if data.get(record..record + 4) == Some(&tag[..]) {
+					let start = u32_at(data, record + 8).ok_or_else(invalid)?;
+					let length = u32_at(data, record + 12).ok_or_else(invalid)?;
+					let start: usize = start.try_into().map_err(|_| invalid())?;
+					let length: usize = length.try_into().map_err(|_| invalid())?;
+					return data
+						.get(start..start.checked_add(length).ok_or_else(invalid)?)
+						.ok_or_else(invalid);
+				}
+			}
+			if tag == b"glyf" {
+				Err(ParseFontError::new("missing `glyf` table"))
+			} else {
+				Err(invalid())
+			}
+		};
+
+		let head = table(b"head")?;
+		let maxp = table(b"maxp")?;
+		let hhea = table(b"hhea")?;
+
+		Ok(Self {
+			id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+			data,
+			cmap: table(b"cmap")?,
+			glyf: table(b"glyf")?,
+			hmtx: table(b"hmtx")?,
+			loca: table(b"loca")?,
+			long_loca: i16_at(head, 50).ok_or_else(invalid)? != 0,
+			glyph_count: u16_at(maxp, 4).ok_or_else(invalid)?,
+			horizontal_metric_count: u16_at(hhea, 34).ok_or_else(invalid)?,
+			units_per_em: u16_at(head, 18)
+				.filter(|units| *units > 0)
+				.ok_or_else(invalid)?,
+			ascent: i16_at(hhea, 4).ok_or_else(invalid)?,
+			descent: i16_at(hhea, 6).ok_or_else(invalid)?,
+			line_gap: i16_at(hhea, 8).ok_or_else(invalid)?,
+		})
+	}
+
+	/// Gets the raw font data.
+	#[must_use]
+	pub fn data(&self) -> &'a [u8] {
+		self.data
+	}
+
+	/// Gets the number of font units per em, which is the font size at 1 pixel per unit.
+	#[must_use]
+	pub fn units_per_em(&self) -> u16 {
+		self.units_per_em
+	}
+
+	/// Gets the distance from the top of a line to its baseline at `size` pixels per em.
+	#[must_use]
+	pub fn ascent(&self, size: f32) -> f32 {
+		f32::from(self.ascent) * self.scale(size)
+	}
+
+	/// Gets the distance between baselines of consecutive lines at `size` pixels per em.
+	#[must_use]
+	pub fn line_height(&self, size: f32) -> f32 {
+		(f32::from(self.ascent) - f32::from(self.descent) + f32::from(self.line_gap))
+			* self.scale(size)
+	}
+
+	/// Gets the horizontal advance of `character` at `size` pixels per em.
+	#[must_use]
+	pub fn advance(&self, character: char, size: f32) -> f32 {
+		f32::from(self.glyph_advance(self.glyph_id(character))) * self.scale(size)
+	}
+
+	/// Gets an identifier unique to the [`OutlineFont::new`] call that loaded this font.
+	pub(crate) fn id(&self) -> usize {
+		self.id
+	}
+
+	fn scale(&self, size: f32) -> f32 {
+		size / f32::from(self.units_per_em)
+	}
+
+	/// Gets the glyph for `character`, falling back to the missing glyph `0`.
+	pub(crate) fn glyph_id(&self, character: char) -> u16 {
+		self.lookup(u32::from(character)).unwrap_or(0)
+	}
+
+	/// Gets the advance of `glyph` in font units.
+	pub(crate) fn glyph_advance(&self, glyph: u16) -> u16 {
+		let index = glyph.min(self.horizontal_metric_count.saturating_sub(1));
+		u16_at(self.hmtx, usize::from(index) * 4).unwrap_or(0)
+	}
+
+	/// Gets the outline of `glyph` in pixels at `size` pixels per em, with the origin on the baseline.
+	pub(crate) fn glyph_path(&self, glyph: u16, size: f32) -> Path {
+		let scale = self.scale(size);
+		let mut components = MAX_COMPONENTS;
+		// Font units grow upwards.
+		self.outline(
+			glyph,
+			Transform::scale(scale, -scale),
+			Path::new(),
+			0,
+			&mut components,
+		)
+		.unwrap_or_default()
+	}
+
+	fn lookup(&self, code_point: u32) -> Option<u16> {
+		let cmap = self.cmap;
+		let table_count = u16_at(cmap, 2)?;
+
+		// Prefer full Unicode tables over BMP-only ones.
+		let mut best_rank = 0;
+		let mut best = None;
+		for i in 0..usize::from(table_count) {
+			let record = 4 + i * 8;
+			let platform = u16_at(cmap, record)?;
+			let encoding = u16_at(cmap, record + 2)?;
+			let offset: usize = u32_at(cmap, record + 4)?.try_into().ok()?;
+			let rank = match (platform, encoding) {
+				(3, 10) | (0, 4 | 6) => 2,
+				(3, 1) | (0, _) => 1,
+				_ => continue,
+			};
+			if rank > best_rank {
+				best_rank = rank;
+				best = Some(offset);
+			}
+		}
+		let subtable = cmap.get(best?..)?;
+
+		let glyph = match u16_at(subtable, 0)? {
+			4 => {
+				let code_point: u16 = code_point.try_into().ok()?;
+				let segment_count = usize::from(u16_at(subtable, 6)? / 2);
+				let end_codes = 14;
+				let start_codes = end_codes + segment_count * 2 + 2;
+				let deltas = start_codes + segment_count * 2;
+				let range_offsets = deltas + segment_count * 2;
+				(0..segment_count).find_map(|i| {
+					if code_point > u16_at(subtable, end_codes + i * 2)? {
+						return None;
+					}
+					let start = u16_at(subtable, start_codes + i * 2)?;
+					if code_point < start {
+						return Some(0);
+					}
+					let delta = u16_at(subtable, deltas + i * 2)?;
+					let range_offset = u16_at(subtable, range_offsets + i * 2)?;
+					if range_offset == 0 {
+						Some(code_point.wrapping_add(delta))
+					} else {
+						let address = range_offsets
+							+ i * 2 + usize::from(range_offset)
+							+ usize::from(code_point - start) * 2;
+						match u16_at(subtable, address)? {
+							0 => Some(0),
+							glyph => Some(glyph.wrapping_add(delta)),
+						}
+					}
+				})?
+			}
+			12 => {
+				// Only groups within the subtable are searched, however many are declared.
+				let group_count: usize = u32_at(subtable, 12)?.try_into().ok()?;
+				let group_count = group_count.min(subtable.len().saturating_sub(16) / 12);
+				(0..group_count).find_map(|i| {
+					let group = 16 + i * 12;
+					let start = u32_at(subtable, group)?;
+					let end = u32_at(subtable, group + 4)?;
+					if (start..=end).contains(&code_point) {
+						// Overflowing glyph IDs are treated as missing.
+						Some(
+							u32_at(subtable, group + 8)?
+								.checked_add(code_point - start)
+								.and_then(|glyph| glyph.try_into().ok())
+								.unwrap_or(0),
+						)
+					} else {
+						None
+					}
+				})?
+			}
+			_ => return None,
+		};
+		Some(glyph).filter(|glyph| *glyph != 0 && *glyph < self.glyph_count)
+	}
+
+	fn glyph_data(&self, glyph: u16) -> Option<&'a [u8]> {
+		if glyph >= self.glyph_count {
+			return None;
+		}
+		let index = usize::from(glyph);
+		let (start, end): (usize, usize) = if self.long_loca {
+			(
+				u32_at(self.loca, index * 4)?.try_into().ok()?,
+				u32_at(self.loca, index * 4 + 4)?.try_into().ok()?,
+			)
+		} else {
+			(
+				usize::from(u16_at(self.loca, index * 2)?) * 2,
+				usize::from(u16_at(self.loca, index * 2 + 2)?) * 2,
+			)
+		};
+		self.glyf.get(start..end)
+	}
+
+	/// Appends the outline of `glyph` to `path`, with `transform` applied.
+	///
+	/// `components` is the number of composite glyph components left, shared across all nesting levels.
+	fn outline(
+		&self,
+		glyph: u16,
+		transform: Transform,
+		path: Path,
+		depth: u8,
+		components: &mut usize,
+	) -> Option<Path> {
+		let data = self.glyph_data(glyph)?;
+		if data.is_empty() {
+			// Glyphs without outline, like spaces.
+			return Some(path);
+		}
+
+		let contour_count = i16_at(data, 0)?;
+		if contour_count >= 0 {
+			simple_outline(data, contour_count.try_into().ok()?, transform, path)
+		} else if depth < 8 {
+			self.composite_outline(data, transform, path, depth, components)
+		} else {
+			None
+		}
+	}
+
+	fn composite_outline(
+		&self,
+		data: &[u8],
+		transform: Transform,
+		mut path: Path,
+		depth: u8,
+		components: &mut usize,
+	) -> Option<Path> {
+		const ARGS_ARE_WORDS: u16 = 0x0001;
+		const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+		const HAS_SCALE: u16 = 0x0008;
+		const MORE_COMPONENTS: u16 = 0x0020;
+		const HAS_XY_SCALE: u16 = 0x0040;
+		const HAS_TWO_BY_TWO: u16 = 0x0080;
+
+		let mut offset = 10;
+		loop {
+			*components = components.checked_sub(1)?;
+			let flags = u16_at(data, offset)?;
+			let component = u16_at(data, offset + 2)?;
+			offset += 4;
+
+			let (dx, dy) = if flags & ARGS_ARE_WORDS == 0 {
+				offset += 2;
+				(
+					f32::from(i8::from_be_bytes([*data.get(offset - 2)?])),
+					f32::from(i8::from_be_bytes([*data.get(offset - 1)?])),
+				)
+			} else {
+				offset += 4;
+				(
+					f32::from(i16_at(data, offset - 4)?),
+					f32::from(i16_at(data, offset - 2)?),
+				)
+			};
+			// Point matching isn't supported, so such components stay in place.
+			let (dx, dy) = if flags & ARGS_ARE_XY_VALUES == 0 {
+				(0., 0.)
+			} else {
+				(dx, dy)
+			};
+
+			let f2dot14 = |offset| i16_at(data, offset).map(|value| f32::from(value) / 16384.);
+			let (a, b, c, d) = if flags & HAS_SCALE != 0 {
+				offset += 2;
+				let scale = f2dot14(offset - 2)?;
+				(scale, 0., 0., scale)
+			} else if flags & HAS_XY_SCALE != 0 {
+				offset += 4;
+				(f2dot14(offset - 4)?, 0., 0., f2dot14(offset - 2)?)
+			} else if flags & HAS_TWO_BY_TWO != 0 {
+				offset += 8;
+				(
+					f2dot14(offset - 8)?,
+					f2dot14(offset - 6)?,
+					f2dot14(offset - 4)?,
+					f2dot14(offset - 2)?,
+				)
+			} else {
+				(1., 0., 0., 1.)
+			};
+
+			let component_transform = Transform {
+				a,
+				b,
+				c,
+				d,
+				e: dx,
+				f: dy,
+			};
+			path = self.outline(
+				component,
+				component_transform.then(transform),
+				path,
+				depth + 1,
+				components,
+			)?;
+
+			if flags & MORE_COMPONENTS == 0 {
+				return Some(path);
+			}
+		}
+	}
+}
+
+fn simple_outline(
+	data: &[u8],
+	contour_count: usize,
+	transform: Transform,
+	mut path: Path,
+) -> Option<Path> {
+	const ON_CURVE: u8 = 0x01;
+	const X_SHORT: u8 = 0x02;
+	const Y_SHORT: u8 = 0x04;
+	const REPEAT: u8 = 0x08;
+	const X_SAME_OR_POSITIVE: u8 = 0x10;
+	const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+	let contour_ends = (0..contour_count)
+		.map(|i| u16_at(data, 10 + i * 2).map(usize::from))
+		.collect::<Option<Vec<_>>>()?;
+	let point_count = contour_ends.last().map_or(0, |last| last + 1);
+	let instruction_length = usize::from(u16_at(data, 10 + contour_count * 2)?);
+	let mut offset = 12 + contour_count * 2 + instruction_length;
+
+	let mut flags = Vec::with_capacity(point_count);
+	while flags.len() < point_count {
+		let flag = *data.get(offset)?;
+		offset += 1;
+		flags.push(flag);
+		if flag & REPEAT != 0 {
+			let repeat = *data.get(offset)?;
+			offset += 1;
+			flags.extend((0..repeat).map(|_| flag));
+		}
+	}
+	flags.truncate(point_count);
+
+	let mut coordinates = |short: u8, same_or_positive: u8| -> Option<Vec<f32>> {
+		let mut value = 0_i32;
+		flags
+			.iter()
+			.map(|flag| {
+				if flag & short != 0 {
+					let delta = i32::from(*data.get(offset)?);
+					offset += 1;
+					value += if flag & same_or_positive == 0 {
+						-delta
+					} else {
+						delta
+					};
+				} else if flag & same_or_positive == 0 {
+					value += i32::from(i16_at(data, offset)?);
+					offset += 2;
+				}
+				#[allow(clippy::cast_precision_loss)]
+				Some(value as f32)
+			})
+			.collect()
+	};
+	let xs = coordinates(X_SHORT, X_SAME_OR_POSITIVE)?;
+	let ys = coordinates(Y_SHORT, Y_SAME_OR_POSITIVE)?;
+
+	let mut start = 0;
+	for end in contour_ends {
+		if end < start || end >= point_count {
+			return None;
+		}
+		let points: Vec<(Point, bool)> = (start..=end)
+			.map(|i| {
+				(
+					transform.apply(Point::new(xs[i], ys[i])),
+					flags[i] & ON_CURVE != 0,
+				)
+			})
+			.collect();
+		path = contour(&points, path);
+		start = end + 1;
+	}
+	Some(path)
+}
+
+/// Appends a closed quadratic contour, where consecutive off-curve points imply on-curve points between them.
+fn contour(points: &[(Point, bool)], mut path: Path) -> Path {
+	let count = points.len();
+	let first_on = points.iter().position(|(_, on_curve)| *on_curve);
+	let (start, first) = match first_on {
+		Some(first_on) => (points[first_on].0, first_on),
+		None if count > 1 => ((points[0].0 + points[1].0) * 0.5, 0),
+		None => return path,
+	};
+
+	path = path.move_to(start);
+	let mut control = None;
+	for i in 1..=count {
+		let (point, on_curve) = points[(first + i) % count];
+		match (on_curve, control) {
+			(true, None) => path = path.line_to(point),
+			(true, Some(previous)) => {
+				path = path.quad_to(previous, point);
+				control = None;
+			}
+			(false, None) => control = Some(point),
+			(false, Some(previous)) => {
+				path = path.quad_to(previous, (previous + point) * 0.5);
+				control = Some(point);
+			}
+		}
+	}
+	if let Some(control) = control {
+		path = path.quad_to(control, start);
+	}
+	path.close()
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+	Some(u16::from_be_bytes(
+		data.get(offset..offset + 2)?.try_into().ok()?,
+	))
+}
+
+fn i16_at(data: &[u8], offset: usize) -> Option<i16> {
+	Some(i16::from_be_bytes(
+		data.get(offset..offset + 2)?.try_into().ok()?,
+	))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+	Some(u32::from_be_bytes(
+		data.get(offset..offset + 4)?.try_into().ok()?,
+	))
+}
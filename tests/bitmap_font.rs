@@ -0,0 +1,197 @@
+//! Bitmap font parsing must reject malformed data with an error instead of panicking or overallocating.
+
+use scanline::text::BitmapFont;
+
+fn psf2(glyph_count: u32, glyph_size: u32, height: u32, width: u32, glyphs: &[u8]) -> Vec<u8> {
+	let mut data = vec![0x72, 0xb5, 0x4a, 0x86];
+	for value in &[0, 32, 0, glyph_count, glyph_size, height, width] {
+		data.extend_from_slice(&value.to_le_bytes());
+	}
+	data.extend_from_slice(glyphs);
+	data
+}
+
+fn psf_error(data: &[u8]) -> String {
+	BitmapFont::from_psf(data)
+		.expect_err("PSF data should be rejected")
+		.to_string()
+}
+
+#[test]
+fn psf() {
+	let mut psf1 = vec![0x36, 0x04, 0, 2];
+	psf1.extend((0..256).flat_map(|index| vec![index as u8, 0xff]));
+	let font = BitmapFont::from_psf(&psf1).unwrap();
+	assert_eq!(font.line_height(), 2);
+	assert_eq!(font.advance('A'), 8);
+
+	let font = BitmapFont::from_psf(&psf2(1, 2, 2, 5, &[0xf8, 0x88])).unwrap();
+	assert_eq!(font.ascent(), 2);
+	assert_eq!(font.advance('\0'), 5);
+	assert_eq!(font.advance('A'), 0);
+}
+
+#[test]
+fn empty_psf_glyphs() {
+	assert_eq!(
+		psf_error(&[0x36, 0x04, 0, 0]),
+		"invalid font data: PSF glyphs are empty"
+	);
+	assert_eq!(
+		psf_error(&psf2(1, 0, 8, 8, &[])),
+		"invalid font data: PSF glyphs are too small"
+	);
+	assert_eq!(
+		psf_error(&psf2(1_000_000, 0, 0, 0, &[])),
+		"invalid font data: PSF glyphs are empty"
+	);
+}
+
+#[test]
+fn malformed_psf() {
+	assert_eq!(
+		psf_error(&[0x36, 0x05, 0, 1]),
+		"invalid font data: unrecognised PSF magic"
+	);
+	assert_eq!(
+		psf_error(&[0x72, 0xb5, 0x4a, 0x86, 0, 0]),
+		"invalid font data: PSF data is truncated"
+	);
+	assert_eq!(
+		psf_error(&psf2(1, 1, 2, 8, &[0])),
+		"invalid font data: PSF glyphs are too small"
+	);
+	assert_eq!(
+		psf_error(&psf2(2, 1, 1, 8, &[0])),
+		"invalid font data: PSF data is truncated"
+	);
+	assert_eq!(
+		psf_error(&psf2(u32::MAX, u32::MAX, 1, 8, &[0])),
+		"invalid font data: PSF data is truncated"
+	);
+}
+
+fn bdf(glyph: &str) -> String {
+	format!(
+		"STARTFONT 2.1\nFONTBOUNDINGBOX 8 8 0 -2\nSTARTCHAR A\nENCODING 65\nDWIDTH 6 0\n{}\nENDFONT\n",
+		glyph
+	)
+}
+
+fn bdf_error(glyph: &str) -> String {
+	BitmapFont::from_bdf(bdf(glyph).as_bytes())
+		.expect_err("BDF data should be rejected")
+		.to_string()
+}
+
+#[test]
+fn bdf_glyphs() {
+	let font =
+		BitmapFont::from_bdf(bdf("BBX 4 2 0 0\nBITMAP\nF0\n90\nENDCHAR").as_bytes()).unwrap();
+	assert_eq!(font.line_height(), 8);
+	assert_eq!(font.ascent(), 6);
+	assert_eq!(font.advance('A'), 6);
+
+	// Empty glyphs, like spaces, are fine.
+	let font = BitmapFont::from_bdf(bdf("BBX 0 0 0 0\nBITMAP\nENDCHAR").as_bytes()).unwrap();
+	assert_eq!(font.advance('A'), 6);
+}
+
+#[test]
+fn malformed_bdf_glyphs() {
+	assert_eq!(
+		BitmapFont::from_bdf(b"STARTFONT 2.1\nSTARTCHAR A\nENCODING 65\nBBX 4 2 0 0\nBITMAP\nF0\n")
+			.unwrap_err()
+			.to_string(),
+		"invalid font data: BDF bitmap is truncated"
+	);
+	// Further bitmap rows are expected instead of `ENDCHAR`.
+	assert_eq!(
+		bdf_error("BBX 4 2 0 0\nBITMAP\nF0\nENDCHAR"),
+		"invalid font data: invalid BDF bitmap row"
+	);
+	assert_eq!(
+		bdf_error("BBX 12 1 0 0\nBITMAP\nF0\nENDCHAR"),
+		"invalid font data: BDF bitmap row too short"
+	);
+	assert_eq!(
+		bdf_error("BBX 4 1 0 0\nBITMAP\nXY\nENDCHAR"),
+		"invalid font data: invalid BDF bitmap row"
+	);
+	assert_eq!(
+		bdf_error("BBX -1 1 0 0\nBITMAP\nF0\nENDCHAR"),
+		"invalid font data: negative BBX width"
+	);
+}
+
+#[test]
+fn oversized_bdf_glyphs() {
+	let huge = isize::MAX;
+	assert_eq!(
+		bdf_error(&format!("BBX {} {} 0 0\nBITMAP\nF0\nENDCHAR", huge, huge)),
+		"invalid font data: BBX is too large"
+	);
+	// These don't overflow, but the bitmap can't supply that many rows or columns.
+	assert_eq!(
+		BitmapFont::from_bdf(
+			format!(
+				"STARTFONT 2.1\nSTARTCHAR A\nENCODING 65\nBBX 1 {} 0 0\nBITMAP\n80\n80\n",
+				huge
+			)
+			.as_bytes()
+		)
+		.unwrap_err()
+		.to_string(),
+		"invalid font data: BDF bitmap is truncated"
+	);
+	assert_eq!(
+		bdf_error(&format!("BBX {} 1 0 0\nBITMAP\n80\nENDCHAR", huge / 2)),
+		"invalid font data: BDF bitmap row too short"
+	);
+}
+
+#[test]
+fn out_of_range_bdf_metrics() {
+	let font_error = |header: &str, glyph: &str| {
+		BitmapFont::from_bdf(
+			format!(
+				"STARTFONT 2.1\n{}\nSTARTCHAR A\nENCODING 65\n{}\nBITMAP\n80\nENDCHAR\nENDFONT\n",
+				header, glyph
+			)
+			.as_bytes(),
+		)
+		.expect_err("BDF data should be rejected")
+		.to_string()
+	};
+	let (min, max) = (isize::MIN, isize::MAX);
+
+	assert_eq!(
+		bdf_error(&format!("BBX 1 1 0 {}\nBITMAP\n80\nENDCHAR", max)),
+		"invalid font data: BBX out of range"
+	);
+	for header in &[
+		format!("FONTBOUNDINGBOX 8 1 0 {}", max),
+		format!("FONTBOUNDINGBOX 8 8 0 {}\nFONT_ASCENT 6", min),
+		format!(
+			"FONTBOUNDINGBOX 8 8 0 0\nFONT_ASCENT {}\nFONT_DESCENT 1",
+			max
+		),
+	] {
+		assert_eq!(
+			font_error(header, "BBX 1 1 0 0"),
+			"invalid font data: font metrics out of range",
+			"{}",
+			header
+		);
+	}
+	assert_eq!(
+		font_error(
+			&format!(
+				"FONTBOUNDINGBOX 8 8 0 0\nFONT_ASCENT {}\nFONT_DESCENT 0",
+				min
+			),
+			"BBX 1 1 0 0"
+		),
+		"invalid font data: glyph position out of range"
+	);
+}
@@ -0,0 +1,174 @@
+//! The vectorized blend kernels must produce exactly the same bytes as straightforward per-pixel blending.
+
+mod common;
+
+use common::{Random, Rgba8};
+use scanline::{
+	drawables::{Bitmap, ColorClip, Solid, ZoomedBitmap},
+	Effect, Sprite,
+};
+use std::{convert::TryInto, num::NonZeroUsize, ops::Range};
+
+/// The scalar reference for sprites.
+fn under(dest: &mut [u8], src: &[u8]) {
+	for (dest, src) in dest.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+		let dest_alpha = u16::from(dest[3]);
+		for (dest, src) in dest.iter_mut().zip(src) {
+			*dest = dest.saturating_add((u16::from(*src) * (255 - dest_alpha) / 255) as u8);
+		}
+	}
+}
+
+/// The scalar reference for effects.
+fn over(dest: &mut [u8], src: &[u8]) {
+	for (dest, src) in dest.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+		let src_alpha = u16::from(src[3]);
+		for (dest, src) in dest.iter_mut().zip(src) {
+			*dest = src.saturating_add((u16::from(*dest) * (255 - src_alpha) / 255) as u8);
+		}
+	}
+}
+
+fn range(range: Range<usize>) -> Range<isize> {
+	range.start.try_into().unwrap()..range.end.try_into().unwrap()
+}
+
+#[test]
+fn bitmap_all_channel_and_alpha_pairs() {
+	// Line `y` of the destination has alpha `y`, and pixel `x` of the bitmap has all channels set to `x`.
+	let src: Vec<u8> = (0..=255).flat_map(|x| vec![x; 4]).collect();
+	let bitmap = Bitmap::<Rgba8>::new(256, &src);
+
+	for y in 0..=255_u8 {
+		let dest: Vec<u8> = (0..=255_u8).flat_map(|x| vec![x, !x, x / 3, y]).collect();
+
+		let mut expected = dest.clone();
+		under(&mut expected, &src);
+		let mut actual = dest.clone();
+		Sprite::render(&bitmap, None, 0, 0..256, 0..256, 0, &mut actual);
+		assert_eq!(actual, expected, "under, dest alpha {}", y);
+
+		// Swapped, so that the source alpha varies per line.
+		let dest = src.clone();
+		let src_line: Vec<u8> = (0..=255_u8).flat_map(|x| vec![x, !x, x / 3, y]).collect();
+		let line = Bitmap::<Rgba8>::new(256, &src_line);
+		let mut expected = dest.clone();
+		over(&mut expected, &src_line);
+		let mut actual = dest;
+		Effect::render(&line, None, 0, 0..256, 0..256, 0, &mut actual);
+		assert_eq!(actual, expected, "over, src alpha {}", y);
+	}
+}
+
+#[test]
+fn bitmap_lengths_and_offsets() {
+	let mut random = Random(0x5EED);
+	let width = 80;
+	let src = random.bytes(width * 4 * 2);
+	let bitmap = Bitmap::<Rgba8>::new(width, &src);
+
+	for start in 0..9 {
+		for end in start..width {
+			let dest = random.bytes((end - start) * 4);
+			let src = &src[(width + start) * 4..(width + end) * 4];
+
+			let mut expected = dest.clone();
+			under(&mut expected, src);
+			let mut actual = dest.clone();
+			Sprite::render(
+				&bitmap,
+				None,
+				1,
+				range(0..width),
+				range(start..end),
+				0,
+				&mut actual,
+			);
+			assert_eq!(actual, expected, "under, {}..{}", start, end);
+
+			let mut expected = dest.clone();
+			over(&mut expected, src);
+			let mut actual = dest;
+			Effect::render(
+				&bitmap,
+				None,
+				1,
+				range(0..width),
+				range(start..end),
+				0,
+				&mut actual,
+			);
+			assert_eq!(actual, expected, "over, {}..{}", start, end);
+		}
+	}
+}
+
+#[test]
+fn zoomed_bitmap() {
+	let mut random = Random(0xB10B);
+	let src = random.bytes(13 * 4 * 3);
+	let two = NonZeroUsize::new(2).unwrap();
+	let bitmap = ZoomedBitmap::<Rgba8>::new(13, &src, 3, two, 1, NonZeroUsize::new(1).unwrap());
+	let zoomed: Vec<u8> = src[13 * 4..13 * 4 * 2]
+		.chunks_exact(4)
+		.flat_map(|pixel| vec![pixel; 3])
+		.step_by(2)
+		.flatten()
+		.copied()
+		.collect();
+
+	for start in 0..5 {
+		for end in start..zoomed.len() / 4 {
+			let dest = random.bytes((end - start) * 4);
+			let src = &zoomed[start * 4..end * 4];
+
+			let mut expected = dest.clone();
+			under(&mut expected, src);
+			let mut actual = dest.clone();
+			Sprite::render(&bitmap, None, 1, 0..19, range(start..end), 0, &mut actual);
+			assert_eq!(actual, expected, "under, {}..{}", start, end);
+
+			let mut expected = dest.clone();
+			over(&mut expected, src);
+			let mut actual = dest;
+			Effect::render(&bitmap, None, 1, 0..19, range(start..end), 0, &mut actual);
+			assert_eq!(actual, expected, "over, {}..{}", start, end);
+		}
+	}
+}
+
+#[test]
+fn flat_colors() {
+	let mut random = Random(0xC010);
+	for color in [
+		[0, 0, 0, 0],
+		[10, 20, 30, 40],
+		[255, 128, 64, 255],
+		[255, 255, 255, 200],
+	] {
+		for len in [0, 1, 3, 4, 7, 8, 9, 63, 64, 65, 100, 333] {
+			let dest = random.bytes(len * 4);
+			let src: Vec<u8> = color.iter().copied().cycle().take(len * 4).collect();
+			let span = range(0..len);
+
+			let solid = Solid::<Rgba8, _>::new(color);
+			let clip = ColorClip::<Rgba8, _, _, _>::new(|_| 0..1, |_, _, span| span, color);
+
+			let mut expected = dest.clone();
+			under(&mut expected, &src);
+			for sprite in [&solid as &dyn Sprite<Rgba8>, &clip] {
+				let mut actual = dest.clone();
+				sprite.render(None, 0, span.clone(), span.clone(), 0, &mut actual);
+				assert_eq!(actual, expected, "under {:?}, {} pixels", color, len);
+			}
+
+			let mut expected = dest.clone();
+			over(&mut expected, &src);
+			for effect in [&solid as &dyn Effect<Rgba8>, &clip] {
+				let mut actual = dest.clone();
+				effect.render(None, 0, span.clone(), span.clone(), 0, &mut actual);
+				assert_eq!(actual, expected, "over {:?}, {} pixels", color, len);
+			}
+		}
+	}
+}
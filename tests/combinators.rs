@@ -0,0 +1,122 @@
+//! Combinators must apply to effects that read the pixels below them.
+
+use scanline::{
+	combinators::{Opacity, SpriteExt, Tint},
+	drawables::Solid,
+	pixel_formats::RgbaNoPadding,
+	Effect, Position, Sprite,
+};
+use std::ops::Range;
+
+type Rgba8 = RgbaNoPadding<8>;
+
+/// Inverts the colour of the pixels below, keeping their alpha.
+struct Invert;
+impl Effect<Rgba8> for Invert {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		0..1
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		line_span
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		_line_span: Range<isize>,
+		_segment: Range<isize>,
+		_offset_bits: usize,
+		data: &mut [u8],
+	) {
+		for pixel in data.chunks_exact_mut(4) {
+			let alpha = pixel[3];
+			for channel in &mut pixel[..3] {
+				*channel = alpha - *channel;
+			}
+		}
+	}
+}
+
+fn render(effect: &impl Effect<Rgba8>, below: &[u8]) -> Vec<u8> {
+	let mut data = below.to_vec();
+	effect.render(Some(0..1), 0, 0..2, 0..2, 0, &mut data);
+	data
+}
+
+const BELOW: [u8; 8] = [0, 100, 200, 255, 10, 20, 30, 40];
+
+#[test]
+fn opacity_fades_effects_reading_below() {
+	assert_eq!(render(&Invert, &BELOW), [255, 155, 55, 255, 30, 20, 10, 40]);
+	assert_eq!(
+		render(&Opacity::new(Invert, 255), &BELOW),
+		render(&Invert, &BELOW)
+	);
+	assert_eq!(render(&Opacity::new(Invert, 0), &BELOW), BELOW);
+	assert_eq!(
+		render(&Opacity::new(Invert, 128), &BELOW),
+		[128, 128, 127, 255, 20, 20, 20, 40]
+	);
+}
+
+#[test]
+fn opacity_fades_source_over_effects() {
+	let solid = Solid::<Rgba8, _>::new([100, 50, 0, 200]);
+	assert_eq!(
+		render(&Opacity::new(solid, 255), &BELOW),
+		render(&solid, &BELOW)
+	);
+	assert_eq!(render(&Opacity::new(solid, 0), &BELOW), BELOW);
+
+	// Same as blending the faded colour.
+	let faded = render(&Opacity::new(solid, 51), &BELOW);
+	let expected = render(&Solid::<Rgba8, _>::new([20, 10, 0, 40]), &BELOW);
+	for (faded, expected) in faded.iter().zip(&expected) {
+		assert!(
+			(i16::from(*faded) - i16::from(*expected)).abs() <= 1,
+			"{:?} vs. {:?}",
+			faded,
+			expected
+		);
+	}
+}
+
+#[test]
+fn tint_source_over_effects() {
+	let solid = Solid::<Rgba8, _>::new([100, 50, 0, 200]);
+	assert_eq!(
+		render(&Tint::new(solid, [255, 255, 255, 255]), &BELOW),
+		render(&solid, &BELOW)
+	);
+	assert_eq!(
+		render(&Tint::new(solid, [0, 255, 0, 255]), &BELOW),
+		render(&Solid::<Rgba8, _>::new([0, 50, 0, 200]), &BELOW)
+	);
+}
+
+#[test]
+fn offset_saturates_unbounded_ranges() {
+	let offset = Solid::<Rgba8, _>::new([255; 4]).offset(Position { x: 0, y: 1 });
+	assert_eq!(Sprite::lines(&offset, None), isize::MIN + 1..isize::MAX);
+	assert_eq!(Effect::lines(&offset, None), isize::MIN + 1..isize::MAX);
+	assert_eq!(Sprite::lines(&offset, Some(0..10)), 0..10);
+
+	let offset = Solid::<Rgba8, _>::new([255; 4]).offset(Position {
+		x: isize::MIN,
+		y: isize::MIN,
+	});
+	assert_eq!(Sprite::lines(&offset, None), isize::MIN..-1);
+	assert_eq!(
+		Sprite::line_segment(&offset, None, 0, isize::MIN..isize::MAX),
+		isize::MIN..-1
+	);
+	// Ranges pushed entirely past the ends of `isize` collapse to empty ones.
+	assert!(Sprite::opaque_segment(&offset, Some(0..10), 0, 0..10).is_empty());
+}
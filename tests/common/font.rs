@@ -0,0 +1,102 @@
+//! Hand-assembled TrueType fonts.
+
+pub fn u16s(values: &[u16]) -> Vec<u8> {
+	values
+		.iter()
+		.flat_map(|value| value.to_be_bytes())
+		.collect()
+}
+
+pub fn u32s(values: &[u32]) -> Vec<u8> {
+	values
+		.iter()
+		.flat_map(|value| value.to_be_bytes())
+		.collect()
+}
+
+/// Assembles an sfnt font from its tables.
+pub fn sfnt(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+	let mut data = u32s(&[0x0001_0000]);
+	data.extend(u16s(&[tables.len() as u16, 0, 0, 0]));
+	let mut offset = 12 + tables.len() * 16;
+	for (tag, table) in tables {
+		data.extend_from_slice(&tag[..]);
+		data.extend(u32s(&[0, offset as u32, table.len() as u32]));
+		offset += table.len();
+	}
+	for (_, table) in tables {
+		data.extend_from_slice(table);
+	}
+	data
+}
+
+/// A square from `(0, 0)` to `(size, size)` font units.
+pub fn square(size: i16) -> Vec<u8> {
+	let size = size as u16;
+	let mut glyph = u16s(&[1, 0, 0, size, size, 3, 0]);
+	glyph.extend(&[0x01; 4]);
+	glyph.extend(u16s(&[0, size, 0, size.wrapping_neg()]));
+	glyph.extend(u16s(&[0, 0, size, 0]));
+	glyph
+}
+
+/// A composite glyph of several `(component, dx)`.
+pub fn composite_of(components: &[(u16, i8)]) -> Vec<u8> {
+	let mut glyph = u16s(&[0xFFFF, 0, 0, 16, 8]);
+	for (i, &(component, dx)) in components.iter().enumerate() {
+		// Each but the last flags `MORE_COMPONENTS`.
+		let flags = if i + 1 < components.len() {
+			0x0022
+		} else {
+			0x0002
+		};
+		glyph.extend(u16s(&[flags, component]));
+		glyph.extend(&[dx as u8, 0]);
+	}
+	glyph
+}
+
+/// A font with 16 units per em, ascent 12, descent -4 and the given `glyphs`.
+///
+/// Glyph 0 advances 1 unit and each other glyph 10 times its ID.
+pub fn font_with_glyphs(cmap: Vec<u8>, glyphs: &[Vec<u8>]) -> Vec<u8> {
+	let mut head = vec![0; 54];
+	head[18..20].copy_from_slice(&16_u16.to_be_bytes());
+	head[50..52].copy_from_slice(&1_u16.to_be_bytes());
+	let maxp = u16s(&[0, 0x5000, glyphs.len() as u16]);
+	let mut hhea = vec![0; 36];
+	hhea[4..10].copy_from_slice(&u16s(&[12, (-4_i16) as u16, 0]));
+	hhea[34..36].copy_from_slice(&(glyphs.len() as u16).to_be_bytes());
+	let hmtx: Vec<u8> = (0..glyphs.len() as u16)
+		.flat_map(|glyph| u16s(&[if glyph == 0 { 1 } else { glyph * 10 }, 0]))
+		.collect();
+
+	let mut loca = vec![0];
+	for glyph in glyphs {
+		loca.push(loca.last().unwrap() + glyph.len() as u32);
+	}
+
+	sfnt(&[
+		(b"cmap", cmap),
+		(b"glyf", glyphs.concat()),
+		(b"head", head),
+		(b"hhea", hhea),
+		(b"hmtx", hmtx),
+		(b"loca", u32s(&loca)),
+		(b"maxp", maxp),
+	])
+}
+
+/// Maps each `[first_code_point, last_code_point, first_glyph]` group, with the given group count.
+pub fn cmap_12_groups(group_count: u32, groups: &[[u32; 3]]) -> Vec<u8> {
+	let mut subtable = u16s(&[12, 0]);
+	subtable.extend(u32s(&[0, 0, group_count]));
+	for group in groups {
+		subtable.extend(u32s(group));
+	}
+
+	let mut cmap = u16s(&[0, 1, 3, 10]);
+	cmap.extend(u32s(&[12]));
+	cmap.extend(subtable);
+	cmap
+}
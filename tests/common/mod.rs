@@ -0,0 +1,111 @@
+//! Fixtures shared by the integration tests, each of which uses only some of them.
+#![allow(dead_code)]
+
+pub mod font;
+
+use scanline::{
+	combinators::{Clip, SpriteExt},
+	drawables::Solid,
+	pixel_formats::RgbaNoPadding,
+	scene::Scene,
+	Position, Sprite,
+};
+use std::{convert::TryInto, ops::Range};
+
+pub type Rgba8 = RgbaNoPadding<8>;
+
+/// A solid colour clipped to a rectangle.
+pub type Rect = Clip<Solid<Rgba8, [u8; 4]>>;
+
+/// A small deterministic xorshift generator.
+pub struct Random(pub u64);
+impl Random {
+	pub fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0 >> 32
+	}
+
+	pub fn below(&mut self, bound: u64) -> isize {
+		(self.next() % bound).try_into().unwrap()
+	}
+
+	/// A non-empty range within `0..=bound`.
+	pub fn range(&mut self, bound: u64) -> Range<isize> {
+		let (a, b) = (self.below(bound), self.below(bound));
+		a.min(b)..a.max(b) + 1
+	}
+
+	/// A position with both coordinates in `-bound..bound`.
+	pub fn position(&mut self, bound: u64) -> Position {
+		let offset: isize = bound.try_into().unwrap();
+		Position {
+			x: self.below(2 * bound) - offset,
+			y: self.below(2 * bound) - offset,
+		}
+	}
+
+	/// A premultiplied colour that's opaque half of the time.
+	pub fn color(&mut self) -> [u8; 4] {
+		let alpha = if self.next() % 2 == 1 {
+			self.next() as u8
+		} else {
+			u8::MAX
+		};
+		let channel = |random: &mut Self| (random.next() % (u64::from(alpha) + 1)) as u8;
+		[channel(self), channel(self), channel(self), alpha]
+	}
+
+	/// A random colour clipped to a rectangle within `0..=lines` and `0..=columns`.
+	pub fn rect(&mut self, lines: u64, columns: u64) -> Box<Rect> {
+		Box::new(Solid::new(self.color()).clip(self.range(lines), self.range(columns)))
+	}
+
+	pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+		(0..len).map(|_| self.next() as u8).collect()
+	}
+}
+
+/// Creates a scene of `count` random rectangles spread over about 70×70 pixels, every third of them an effect.
+pub fn scene(random: &mut Random, count: i32) -> Scene<Rgba8, Rect, Rect> {
+	let mut scene = Scene::new();
+	for z in 0..count {
+		let (rect, position) = (random.rect(60, 60), random.position(10));
+		if z % 3 == 0 {
+			scene.insert_effect(rect, position, z);
+		} else {
+			scene.insert_sprite(rect, position, z);
+		}
+	}
+	scene
+}
+
+/// Renders `sprite` onto transparency over `width`×`height` pixels and collects their alpha by line.
+pub fn alphas(sprite: &impl Sprite<Rgba8>, width: usize, height: usize) -> Vec<Vec<u8>> {
+	let (lines, span) = (0..height as isize, 0..width as isize);
+	lines
+		.clone()
+		.map(|line| {
+			let mut data = vec![0; width * 4];
+			sprite.render(
+				Some(lines.clone()),
+				line,
+				span.clone(),
+				span.clone(),
+				0,
+				&mut data,
+			);
+			data.chunks_exact(4).map(|pixel| pixel[3]).collect()
+		})
+		.collect()
+}
+
+/// Sums the coverage of `alphas`, in pixels.
+pub fn area(alphas: &[Vec<u8>]) -> f32 {
+	alphas
+		.iter()
+		.flatten()
+		.map(|alpha| f32::from(*alpha) / 255.)
+		.sum()
+}
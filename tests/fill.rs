@@ -0,0 +1,200 @@
+//! Filled paths must approximate their curves within the tolerance and apply their fill rule.
+
+mod common;
+
+use common::{alphas, area, Rgba8};
+use scanline::{
+	drawables::{Fill, FillRule, Solid},
+	geometry::{Path, Point},
+};
+use std::f32::consts::PI;
+
+const SIZE: usize = 16;
+
+fn fill(path: &Path, tolerance: f32, fill_rule: FillRule) -> Vec<Vec<u8>> {
+	let fill = Fill::new(path, tolerance, fill_rule, Solid::<Rgba8, _>::new([255; 4]));
+	alphas(&fill, SIZE, SIZE)
+}
+
+/// A circle with radius 6 around (8, 8), made of four cubic Bézier curves.
+fn circle() -> Path {
+	let handle = 6. * 4. / 3. * (PI / 8.).tan();
+	let (left, top, right, bottom) = (2., 2., 14., 14.);
+	Path::new()
+		.move_to(Point::new(right, 8.))
+		.cubic_to(
+			Point::new(right, 8. + handle),
+			Point::new(8. + handle, bottom),
+			Point::new(8., bottom),
+		)
+		.cubic_to(
+			Point::new(8. - handle, bottom),
+			Point::new(left, 8. + handle),
+			Point::new(left, 8.),
+		)
+		.cubic_to(
+			Point::new(left, 8. - handle),
+			Point::new(8. - handle, top),
+			Point::new(8., top),
+		)
+		.cubic_to(
+			Point::new(8. + handle, top),
+			Point::new(right, 8. - handle),
+			Point::new(right, 8.),
+		)
+		.close()
+}
+
+#[test]
+fn flattening_tolerance() {
+	let expected = PI * 36.;
+	let mut previous_error = 0.;
+	for &tolerance in &[0.01, 0.1, 0.5, 2.] {
+		let error = expected - area(&fill(&circle(), tolerance, FillRule::NonZero));
+		// Flattened curves lie inside the circle, at most `tolerance` away from it.
+		assert!(error > -0.1, "{}: {}", tolerance, error);
+		assert!(
+			error < tolerance * 2. * PI * 6. + 0.1,
+			"{}: {}",
+			tolerance,
+			error
+		);
+		assert!(error + 0.1 >= previous_error, "{}: {}", tolerance, error);
+		previous_error = error;
+	}
+	assert!(previous_error > 1.);
+
+	// A huge tolerance reduces each curve to a straight line.
+	let diamond = area(&fill(&circle(), 100., FillRule::NonZero));
+	assert!((diamond - 72.).abs() < 0.1, "{}", diamond);
+}
+
+fn arc(large_arc: bool, sweep: bool, radius: f32, to: Point) -> Path {
+	Path::new()
+		.move_to(Point::new(8., 2.))
+		.arc_to(Point::new(radius, radius), 0., large_arc, sweep, to)
+		.close()
+}
+
+#[test]
+fn arcs() {
+	// The quarter arcs to (14, 8) around (8, 8) and (14, 2) bulge to opposite sides of their chord.
+	let segment = 9. * PI - 18.;
+	let clockwise = fill(
+		&arc(false, true, 6., Point::new(14., 8.)),
+		0.01,
+		FillRule::NonZero,
+	);
+	assert!(
+		(area(&clockwise) - segment).abs() < 0.2,
+		"{}",
+		area(&clockwise)
+	);
+	assert_eq!((clockwise[4][11], clockwise[5][10]), (255, 0));
+	let counterclockwise = fill(
+		&arc(false, false, 6., Point::new(14., 8.)),
+		0.01,
+		FillRule::NonZero,
+	);
+	assert!((area(&counterclockwise) - segment).abs() < 0.2);
+	assert_eq!((counterclockwise[4][11], counterclockwise[5][10]), (0, 255));
+
+	// The large counterclockwise arc goes the long way around (8, 8), leaving out the clockwise segment.
+	let large = fill(
+		&arc(true, false, 6., Point::new(14., 8.)),
+		0.01,
+		FillRule::NonZero,
+	);
+	assert_eq!((large[4][11], large[5][10], large[8][8]), (0, 255, 255));
+
+	// Radii too small to reach the end point are scaled up into a half circle.
+	let half = fill(
+		&arc(false, true, 1., Point::new(8., 14.)),
+		0.01,
+		FillRule::NonZero,
+	);
+	assert!((area(&half) - 18. * PI).abs() < 0.3, "{}", area(&half));
+	assert!(half
+		.iter()
+		.all(|line| line[..8].iter().all(|alpha| *alpha == 0)));
+
+	// A zero radius draws a straight line, which encloses nothing.
+	let line = fill(
+		&arc(false, true, 0., Point::new(14., 8.)),
+		0.01,
+		FillRule::NonZero,
+	);
+	assert_eq!(area(&line), 0.);
+}
+
+#[test]
+fn self_intersecting_star() {
+	let vertex = |i: usize| {
+		let angle = -PI / 2. + i as f32 * 4. * PI / 5.;
+		Point::new(8. + 7. * angle.cos(), 8. + 7. * angle.sin())
+	};
+	let mut star = Path::new().move_to(vertex(0));
+	for i in 1..5 {
+		star = star.line_to(vertex(i));
+	}
+	let star = star.close();
+
+	let non_zero = fill(&star, 0.1, FillRule::NonZero);
+	let even_odd = fill(&star, 0.1, FillRule::EvenOdd);
+
+	// The inner pentagon is wound around twice.
+	assert_eq!(non_zero[8][8], 255);
+	assert_eq!(even_odd[8][8], 0);
+	let inner_radius = 7. * (2. * PI / 5.).cos() / (PI / 5.).cos();
+	let pentagon = 2.5 * inner_radius * inner_radius * (2. * PI / 5.).sin();
+	let difference = area(&non_zero) - area(&even_odd);
+	assert!(
+		(difference - pentagon).abs() < 0.2,
+		"{} {}",
+		difference,
+		pentagon
+	);
+
+	// The points are wound around once.
+	for (non_zero, even_odd) in non_zero.iter().flatten().zip(even_odd.iter().flatten()) {
+		assert!(non_zero >= even_odd);
+	}
+	assert_eq!(non_zero[2][8], even_odd[2][8]);
+	assert_ne!(non_zero[2][8], 0);
+}
+
+fn square(path: Path, from: f32, to: f32, clockwise: bool) -> Path {
+	let (a, b) = if clockwise {
+		(Point::new(to, from), Point::new(from, to))
+	} else {
+		(Point::new(from, to), Point::new(to, from))
+	};
+	path.move_to(Point::new(from, from))
+		.line_to(a)
+		.line_to(Point::new(to, to))
+		.line_to(b)
+		.close()
+}
+
+#[test]
+fn nested_squares() {
+	let same = square(square(Path::new(), 2., 14., true), 5., 11., true);
+	let opposite = square(square(Path::new(), 2., 14., true), 5., 11., false);
+
+	for path in &[&same, &opposite] {
+		for &rule in &[FillRule::NonZero, FillRule::EvenOdd] {
+			let alphas = fill(path, 0.1, rule);
+			assert_eq!(alphas[3][3], 255);
+			assert_eq!(alphas[8][2..5], [255; 3]);
+			assert_eq!(alphas[1], [0; SIZE]);
+		}
+	}
+
+	// Only the non-zero rule fills an inner square wound the same way.
+	assert_eq!(fill(&same, 0.1, FillRule::NonZero)[8][5..11], [255; 6]);
+	assert_eq!(fill(&same, 0.1, FillRule::EvenOdd)[8][5..11], [0; 6]);
+	assert_eq!(fill(&opposite, 0.1, FillRule::NonZero)[8][5..11], [0; 6]);
+	assert_eq!(fill(&opposite, 0.1, FillRule::EvenOdd)[8][5..11], [0; 6]);
+	assert_eq!(area(&fill(&same, 0.1, FillRule::NonZero)), 144.);
+	assert_eq!(area(&fill(&same, 0.1, FillRule::EvenOdd)), 144. - 36.);
+}
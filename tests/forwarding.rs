@@ -0,0 +1,168 @@
+//! Smart pointers and [`Option`]s must forward to their contents, and collections must render as stacks of layers.
+
+mod common;
+
+use common::{Rect, Rgba8};
+use scanline::{
+	combinators::SpriteExt, drawables::Solid, render_segment, Effect, Position, Sprite,
+};
+use std::{ops::Range, rc::Rc, sync::Arc};
+
+const ORIGIN: Position = Position { x: 0, y: 0 };
+
+fn rect(color: [u8; 4], lines: Range<isize>, columns: Range<isize>) -> Rect {
+	Solid::new(color).clip(lines, columns)
+}
+
+/// Three overlapping rectangles on lines `0..7`, translucent except for the second.
+fn layers() -> [Rect; 3] {
+	[
+		rect([128, 0, 0, 128], 0..4, 0..4),
+		rect([0, 0, 255, 255], 2..6, 2..6),
+		rect([0, 100, 0, 100], 1..7, 3..5),
+	]
+}
+
+/// Renders 8×8 pixels of `sprites` and `effects` in order, with an opaque white background behind all sprites.
+fn image(sprites: &[Rc<dyn Sprite<Rgba8>>], effects: &[Rc<dyn Effect<Rgba8>>]) -> Vec<u8> {
+	let background: Rc<dyn Sprite<Rgba8>> = Rc::new(Solid::new([255; 4]));
+	let mut image = Vec::new();
+	for line in 0..8 {
+		let mut buffer = vec![0; 8 * 4];
+		render_segment(
+			&Some(0..8),
+			line,
+			0..8,
+			0..8,
+			&mut buffer,
+			sprites
+				.iter()
+				.chain(Some(&background))
+				.map(|sprite| (ORIGIN, Rc::clone(sprite))),
+			effects.iter().map(|effect| (ORIGIN, Rc::clone(effect))),
+		);
+		image.extend(buffer);
+	}
+	image
+}
+
+fn sprites() -> Vec<Rc<dyn Sprite<Rgba8>>> {
+	layers()
+		.iter()
+		.map(|layer| Rc::new(layer.clone()) as Rc<dyn Sprite<Rgba8>>)
+		.collect()
+}
+
+#[test]
+fn sprite_stacks() {
+	let [a, b, c] = layers();
+	let expected = image(&sprites(), &[]);
+	assert_ne!(
+		image(&[Rc::new((c.clone(), b.clone(), a.clone()))], &[]),
+		expected
+	);
+
+	let stacks: Vec<Rc<dyn Sprite<Rgba8>>> = vec![
+		Rc::new((a.clone(), b.clone(), c.clone())),
+		Rc::new((a.clone(), (b.clone(), c.clone()))),
+		Rc::new(layers()),
+		Rc::new(layers().to_vec()),
+		Rc::new(layers().to_vec().into_boxed_slice()),
+	];
+	for stack in stacks {
+		assert_eq!(image(&[stack], &[]), expected);
+	}
+
+	// Stacks span all their layers.
+	let stack = (a, b, c);
+	assert_eq!(Sprite::lines(&stack, Some(0..8)), 0..7);
+	assert_eq!(Sprite::line_segment(&stack, Some(0..8), 0, 0..8), 0..4);
+	assert_eq!(Sprite::line_segment(&stack, Some(0..8), 3, 0..8), 0..6);
+	assert_eq!(Sprite::line_segment(&stack, Some(0..8), 6, 0..8), 3..5);
+	assert_eq!(Sprite::lines(&Vec::<Rect>::new(), Some(0..8)), 0..0);
+}
+
+#[test]
+fn effect_stacks() {
+	let [a, b, c] = layers();
+	let sprites = sprites();
+	let effects: Vec<Rc<dyn Effect<Rgba8>>> = layers()
+		.iter()
+		.map(|layer| Rc::new(layer.clone()) as Rc<dyn Effect<Rgba8>>)
+		.collect();
+	let expected = image(&sprites, &effects);
+	assert_ne!(
+		image(&sprites, &[Rc::new((c.clone(), b.clone(), a.clone()))]),
+		expected
+	);
+
+	let stacks: Vec<Rc<dyn Effect<Rgba8>>> = vec![
+		Rc::new((a.clone(), b.clone(), c)),
+		Rc::new(((a, b), layers()[2].clone())),
+		Rc::new(layers()),
+		Rc::new(layers().to_vec()),
+		Rc::new(layers().to_vec().into_boxed_slice()),
+	];
+	for stack in stacks {
+		assert_eq!(image(&sprites, &[stack]), expected);
+	}
+}
+
+#[test]
+fn pointers_and_options() {
+	let [a, b, _] = layers();
+	let expected = image(&[Rc::new(a.clone())], &[]);
+	let sprites: Vec<Rc<dyn Sprite<Rgba8>>> = vec![
+		Rc::new(Box::new(a.clone())),
+		Rc::new(Rc::new(a.clone())),
+		Rc::new(Arc::new(a.clone())),
+		Rc::new(Some(a.clone())),
+		Rc::new(Box::new(a.clone()) as Box<dyn Sprite<Rgba8>>),
+	];
+	for sprite in sprites {
+		assert_eq!(image(&[sprite], &[]), expected);
+	}
+	let effects: Vec<Rc<dyn Effect<Rgba8>>> = vec![
+		Rc::new(Box::new(a.clone())),
+		Rc::new(Rc::new(a.clone())),
+		Rc::new(Arc::new(a.clone())),
+		Rc::new(Some(a.clone())),
+	];
+	for effect in effects {
+		assert_eq!(image(&[], &[effect]), image(&[], &[Rc::new(a.clone())]));
+	}
+
+	// Only sprites forward their opaque segments.
+	assert_eq!(
+		Box::new(b.clone()).opaque_segment(Some(0..8), 3, 0..8),
+		2..6
+	);
+	assert_eq!(
+		Arc::new(b.clone()).opaque_segment(Some(0..8), 3, 0..8),
+		2..6
+	);
+	assert_eq!(Some(b).opaque_segment(Some(0..8), 3, 0..8), 2..6);
+}
+
+#[test]
+fn none_renders_nothing() {
+	let none: Option<Rect> = None;
+	assert_eq!(Sprite::lines(&none, None), 0..0);
+	assert_eq!(Effect::lines(&none, None), 0..0);
+	assert_eq!(Sprite::line_segment(&none, None, 0, 0..8), 0..0);
+	assert_eq!(Effect::line_segment(&none, None, 0, 0..8), 0..0);
+	assert_eq!(none.opaque_segment(None, 0, 0..8), 0..0);
+
+	let mut data = [1; 8 * 4];
+	Sprite::render(&none, Some(0..8), 0, 0..8, 0..8, 0, &mut data);
+	Effect::render(&none, Some(0..8), 0, 0..8, 0..8, 0, &mut data);
+	assert_eq!(data, [1; 8 * 4]);
+
+	assert_eq!(
+		image(
+			&[Rc::new(none.clone()), Rc::new(layers()[0].clone())],
+			&[Rc::new(none)]
+		),
+		image(&[Rc::new(layers()[0].clone())], &[])
+	);
+}
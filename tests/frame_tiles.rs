@@ -0,0 +1,61 @@
+//! Tiles must reassemble to exactly the lines rendered by [`Frame::render_line`].
+
+mod common;
+
+use common::Random;
+use scanline::frame::Frame;
+
+#[test]
+fn tiles_reassemble_to_lines() {
+	let mut random = Random(0x7115_7115_7115_7115);
+	let scene = common::scene(&mut random, 16);
+
+	for &(width, height) in &[(57, 43), (64, 32), (1, 1), (5, 70)] {
+		let frame = Frame::new(&scene, width, height);
+		let lines: Vec<Vec<u8>> = frame.lines().collect();
+		for line in &lines {
+			assert_eq!(line.len(), frame.line_len());
+		}
+
+		for &(tile_width, tile_height) in &[(1, 1), (8, 8), (16, 5), (13, 64), (100, 100)] {
+			let tiles = frame.tiles(tile_width, tile_height);
+			#[allow(clippy::manual_div_ceil)]
+			let count = (width + tile_width - 1) / tile_width * ((height + tile_height - 1) / tile_height);
+			assert_eq!(tiles.len(), count);
+
+			let mut reassembled = vec![vec![0xAB; frame.line_len()]; height];
+			let mut covered = vec![vec![0_u8; width]; height];
+			for tile in tiles {
+				assert_eq!(tile.data.len(), tile.width * tile.height * 4);
+				assert_eq!(
+					(tile.x, tile.y),
+					(tile.column * tile_width, tile.row * tile_height)
+				);
+				for (y, row) in tile.data.chunks_exact(tile.width * 4).enumerate() {
+					reassembled[tile.y + y][tile.x * 4..(tile.x + tile.width) * 4]
+						.copy_from_slice(row);
+					for covered in &mut covered[tile.y + y][tile.x..tile.x + tile.width] {
+						*covered += 1;
+					}
+				}
+			}
+
+			// Each pixel is in exactly one tile.
+			assert!(covered.iter().flatten().all(|count| *count == 1));
+			assert!(
+				reassembled == lines,
+				"{}x{} tiles of {}x{}",
+				tile_width,
+				tile_height,
+				width,
+				height
+			);
+		}
+
+		// Back to front yields the same tiles in reverse.
+		let forwards: Vec<_> = frame.tiles(16, 16).collect();
+		let mut backwards: Vec<_> = frame.tiles(16, 16).rev().collect();
+		backwards.reverse();
+		assert_eq!(forwards, backwards);
+	}
+}
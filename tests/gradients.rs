@@ -0,0 +1,165 @@
+//! Gradients must take the documented colours at their defining points, when spread and in degenerate cases.
+
+use scanline::{
+	drawables::{ColorStop, ConicGradient, LinearGradient, RadialGradient, Spread},
+	geometry::Point,
+	pixel_formats::RgbaNoPadding,
+	Sprite,
+};
+use std::{f32::consts::PI, ops::Range};
+
+type Rgba8 = RgbaNoPadding<8>;
+type Lines = fn(Option<Range<isize>>) -> Range<isize>;
+type Segments = fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>;
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+const PURPLE: [u8; 4] = [128, 0, 128, 255];
+
+fn red_to_blue() -> Vec<ColorStop> {
+	vec![
+		ColorStop {
+			offset: 0.,
+			color: RED,
+		},
+		ColorStop {
+			offset: 1.,
+			color: BLUE,
+		},
+	]
+}
+
+/// Renders `width` pixels of `line` onto transparency.
+fn pixels(gradient: &impl Sprite<Rgba8>, line: isize, width: usize) -> Vec<[u8; 4]> {
+	let span = 0..width as isize;
+	let mut data = vec![0; width * 4];
+	gradient.render(Some(0..16), line, span.clone(), span, 0, &mut data);
+	data.chunks_exact(4)
+		.map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3]])
+		.collect()
+}
+
+fn radial(
+	center: Point,
+	radius: f32,
+	focus: Point,
+	spread: Spread,
+) -> RadialGradient<Rgba8, Lines, Segments> {
+	RadialGradient::new(
+		|_| 0..16,
+		|_, _, line_span| line_span,
+		center,
+		radius,
+		focus,
+		red_to_blue(),
+		spread,
+	)
+}
+
+fn conic(center: Point, start_angle: f32) -> ConicGradient<Rgba8, Lines, Segments> {
+	ConicGradient::new(
+		|_| 0..16,
+		|_, _, line_span| line_span,
+		center,
+		start_angle,
+		red_to_blue(),
+	)
+}
+
+#[test]
+fn degenerate_linear_gradient_uses_last_stop() {
+	let point = Point { x: 2., y: 3. };
+	for &spread in &[Spread::Pad, Spread::Repeat, Spread::Reflect] {
+		let gradient = LinearGradient::<RgbaNoPadding<8>, _, _>::new(
+			|_| 0..4,
+			|_, _, line_span: Range<isize>| line_span,
+			point,
+			point,
+			vec![
+				ColorStop {
+					offset: 0.,
+					color: [255, 0, 0, 255],
+				},
+				ColorStop {
+					offset: 1.,
+					color: [0, 0, 255, 255],
+				},
+			],
+			spread,
+		);
+
+		let mut data = vec![0; 4 * 4];
+		Sprite::render(&gradient, Some(0..4), 1, 0..4, 0..4, 0, &mut data);
+		assert!(
+			data.chunks_exact(4).all(|pixel| pixel == [0, 0, 255, 255]),
+			"{:?}: {:?}",
+			spread,
+			data
+		);
+	}
+}
+
+#[test]
+fn radial_gradient_runs_from_focus_to_circle() {
+	let center = Point::new(8.5, 8.5);
+
+	let centered = pixels(&radial(center, 8., center, Spread::Pad), 8, 20);
+	assert_eq!(centered[8], RED);
+	assert_eq!(centered[12], PURPLE);
+	assert_eq!(centered[16], BLUE);
+	assert_eq!(centered[19], BLUE);
+	assert_eq!(centered[4], PURPLE);
+	assert_eq!(centered[0], BLUE);
+
+	let focal = pixels(
+		&radial(center, 8., Point::new(4.5, 8.5), Spread::Pad),
+		8,
+		20,
+	);
+	assert_eq!(focal[4], RED);
+	assert_eq!(focal[16], BLUE);
+	assert_eq!(focal[0], BLUE);
+	assert_ne!(focal[10], centered[10]);
+}
+
+#[test]
+fn radial_gradient_spreads() {
+	let center = Point::new(0.5, 0.5);
+	let expected = [
+		(Spread::Pad, [RED, PURPLE, BLUE, BLUE, BLUE]),
+		(Spread::Repeat, [RED, PURPLE, RED, PURPLE, RED]),
+		(Spread::Reflect, [RED, PURPLE, BLUE, PURPLE, RED]),
+	];
+	for &(spread, expected) in &expected {
+		let pixels = pixels(&radial(center, 4., center, spread), 0, 9);
+		let sampled: Vec<_> = pixels.iter().step_by(2).copied().collect();
+		assert_eq!(sampled, expected, "{:?}", spread);
+	}
+}
+
+#[test]
+fn conic_gradient_seam() {
+	let gradient = conic(Point::new(4., 4.), 0.);
+
+	// Just above the positive x axis, the turn is almost complete.
+	let above = pixels(&gradient, 3, 8)[7];
+	assert!(above[0] < 16 && above[2] > 240, "{:?}", above);
+	// Just below it, the turn has barely started.
+	let below = pixels(&gradient, 4, 8)[7];
+	assert!(below[0] > 240 && below[2] < 16, "{:?}", below);
+
+	// The negative x axis is halfway around and continuous.
+	let above = pixels(&gradient, 3, 8)[0];
+	let below = pixels(&gradient, 4, 8)[0];
+	for (above, below) in above.iter().zip(&below) {
+		assert!((i16::from(*above) - i16::from(*below)).abs() <= 16);
+	}
+	assert!((i16::from(above[0]) - 128).abs() <= 8, "{:?}", above);
+}
+
+#[test]
+fn conic_gradient_start_angle() {
+	let gradient = conic(Point::new(4.5, 4.), PI / 2.);
+	assert_eq!(pixels(&gradient, 7, 8)[4], RED);
+	assert_eq!(pixels(&gradient, 0, 8)[4], PURPLE);
+}
@@ -0,0 +1,106 @@
+//! Masks must scale their content's opacity by alpha or luminance, only where both overlap.
+
+mod common;
+
+use common::Rgba8;
+use scanline::{
+	combinators::{Clip, Opacity, SpriteExt},
+	drawables::{MaskMode, Solid},
+	Effect, Sprite,
+};
+
+type Rect = Clip<Solid<Rgba8, [u8; 4]>>;
+
+const CONTENT: [u8; 4] = [200, 100, 50, 255];
+
+/// Lines `0..10`, columns `0..10`.
+fn content() -> Rect {
+	Solid::new(CONTENT).clip(0..10, 0..10)
+}
+
+/// Lines `5..15`, columns `3..20`.
+fn mask(color: [u8; 4]) -> Rect {
+	Solid::new(color).clip(5..15, 3..20)
+}
+
+fn line(sprite: &impl Sprite<Rgba8>, line: isize) -> Vec<u8> {
+	let mut data = vec![0; 4 * 20];
+	sprite.render(Some(0..20), line, 0..20, 0..20, 0, &mut data);
+	data
+}
+
+#[test]
+fn overlap() {
+	let masked = content().masked(mask([0, 0, 0, 255]), MaskMode::Alpha);
+	assert_eq!(Sprite::lines(&masked, None), 5..10);
+	assert_eq!(Sprite::lines(&masked, Some(0..20)), 5..10);
+	assert_eq!(Sprite::line_segment(&masked, Some(0..20), 6, 0..20), 3..10);
+	assert_eq!(Effect::line_segment(&masked, Some(0..20), 6, 0..20), 3..10);
+
+	// Disjoint masks hide everything.
+	let disjoint = content().masked(Solid::new([255; 4]).clip(10..20, 0..10), MaskMode::Alpha);
+	assert!(Sprite::lines(&disjoint, None).is_empty());
+	let beside = content().masked(Solid::new([255; 4]).clip(0..10, 10..20), MaskMode::Alpha);
+	assert!(Sprite::line_segment(&beside, Some(0..20), 5, 0..20).is_empty());
+
+	// Outside the overlap, nothing is drawn even where one of them covers.
+	let rendered = line(&masked, 6);
+	assert!(rendered[..3 * 4].iter().all(|channel| *channel == 0));
+	assert!(rendered[10 * 4..].iter().all(|channel| *channel == 0));
+	assert_eq!(rendered[3 * 4..10 * 4], line(&content(), 6)[3 * 4..10 * 4]);
+}
+
+#[test]
+fn alpha_mode() {
+	// Only opacity counts, so an opaque black mask shows its content fully.
+	for (color, opacity) in [([0, 0, 0, 255], 255), ([128, 0, 0, 128], 128), ([0; 4], 0)] {
+		assert_eq!(
+			line(&content().masked(mask(color), MaskMode::Alpha), 6)[3 * 4..10 * 4],
+			line(&Opacity::new(content(), opacity), 6)[3 * 4..10 * 4],
+			"{:?}",
+			color
+		);
+	}
+}
+
+#[test]
+fn luminance_mode() {
+	// Red, green and blue weigh 54, 183 and 19 of 256, applied to premultiplied channels.
+	for (color, opacity) in [
+		([255, 255, 255, 255], 255),
+		([0, 0, 0, 255], 0),
+		([255, 0, 0, 255], 53),
+		([0, 255, 0, 255], 182),
+		([0, 0, 255, 255], 18),
+		([128, 128, 128, 128], 128),
+	] {
+		assert_eq!(
+			line(&content().masked(mask(color), MaskMode::Luminance), 6)[3 * 4..10 * 4],
+			line(&Opacity::new(content(), opacity), 6)[3 * 4..10 * 4],
+			"{:?}",
+			color
+		);
+	}
+}
+
+#[test]
+fn effects_blend_over() {
+	let masked = content().masked(mask([0, 0, 0, 128]), MaskMode::Alpha);
+	let below = [0, 0, 255, 255].repeat(20);
+
+	let mut data = below.clone();
+	Effect::render(&masked, Some(0..20), 6, 0..20, 0..20, 0, &mut data);
+	let mut expected = below.clone();
+	Effect::render(
+		&Opacity::new(content(), 128),
+		Some(0..20),
+		6,
+		0..20,
+		0..20,
+		0,
+		&mut expected,
+	);
+	assert_eq!(data[..3 * 4], below[..3 * 4]);
+	assert_eq!(data[3 * 4..10 * 4], expected[3 * 4..10 * 4]);
+	assert_eq!(data[10 * 4..], below[10 * 4..]);
+}
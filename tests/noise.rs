@@ -0,0 +1,106 @@
+//! Noise must be deterministic: the same seed always produces the same image.
+
+mod common;
+
+use common::{alphas, Rgba8};
+use scanline::drawables::{ColorStop, Noise, NoiseKind};
+use std::ops::Range;
+
+fn lines(_: Option<Range<isize>>) -> Range<isize> {
+	0..4
+}
+
+fn segments(_: Option<Range<isize>>, _: isize, _: Range<isize>) -> Range<isize> {
+	0..8
+}
+
+type Lines = fn(Option<Range<isize>>) -> Range<isize>;
+type Segments = fn(Option<Range<isize>>, isize, Range<isize>) -> Range<isize>;
+
+/// Noise from transparent to opaque white, so that alpha is the noise value.
+fn noise(kind: NoiseKind, seed: u64) -> Noise<Rgba8, Lines, Segments> {
+	Noise::new(
+		lines,
+		segments,
+		kind,
+		seed,
+		3.,
+		[
+			ColorStop {
+				offset: 0.,
+				color: [0; 4],
+			},
+			ColorStop {
+				offset: 1.,
+				color: [255; 4],
+			},
+		],
+	)
+}
+
+type Image = [[u8; 8]; 4];
+
+/// Alpha of 8×4 pixels with seed `42`, as plain noise and with three octaves.
+///
+/// These must never change, as images are meant to be reproducible across versions and platforms.
+const GOLDEN: [(NoiseKind, Image, Image); 3] = [
+	(
+		NoiseKind::Value,
+		[
+			[152, 164, 176, 175, 148, 122, 117, 74],
+			[155, 160, 164, 164, 157, 150, 148, 119],
+			[158, 155, 153, 153, 166, 179, 179, 164],
+			[159, 154, 148, 149, 162, 176, 176, 164],
+		],
+		[
+			[154, 132, 165, 163, 121, 116, 114, 82],
+			[181, 159, 132, 121, 153, 150, 154, 122],
+			[169, 146, 116, 106, 126, 163, 159, 147],
+			[147, 132, 125, 115, 142, 143, 146, 120],
+		],
+	),
+	(
+		NoiseKind::Perlin,
+		[
+			[129, 92, 96, 102, 128, 152, 154, 156],
+			[164, 82, 68, 101, 150, 152, 128, 86],
+			[125, 62, 91, 153, 191, 138, 89, 47],
+			[80, 40, 90, 155, 210, 175, 129, 90],
+		],
+		[
+			[111, 117, 95, 102, 131, 126, 139, 153],
+			[153, 102, 66, 124, 140, 160, 126, 104],
+			[127, 98, 113, 132, 138, 140, 97, 94],
+			[108, 76, 108, 120, 181, 161, 107, 104],
+		],
+	),
+	(
+		NoiseKind::Simplex,
+		[
+			[124, 82, 71, 117, 17, 39, 141, 220],
+			[72, 72, 246, 245, 128, 136, 218, 156],
+			[35, 84, 187, 222, 156, 127, 232, 26],
+			[35, 31, 45, 128, 166, 17, 145, 108],
+		],
+		[
+			[133, 66, 111, 121, 57, 85, 128, 193],
+			[123, 112, 193, 191, 112, 119, 185, 162],
+			[66, 67, 173, 223, 125, 109, 188, 97],
+			[33, 94, 56, 138, 194, 54, 175, 142],
+		],
+	),
+];
+
+#[test]
+fn golden_bytes() {
+	for (kind, plain, fbm) in GOLDEN {
+		assert_eq!(alphas(&noise(kind, 42), 8, 4), plain, "{:?}", kind);
+		assert_eq!(
+			alphas(&noise(kind, 42).fbm(3, 2., 0.5), 8, 4),
+			fbm,
+			"{:?} fBm",
+			kind
+		);
+		assert_ne!(alphas(&noise(kind, 43), 8, 4), plain, "{:?}", kind);
+	}
+}
@@ -0,0 +1,113 @@
+//! Skipping sprites hidden behind opaque segments must not change what's rendered.
+
+mod common;
+
+use common::{Random, Rgba8};
+use scanline::{
+	combinators::SpriteExt,
+	drawables::{ColorClip, Solid},
+	render_segment, Effect, Position, Sprite,
+};
+use std::ops::Range;
+
+/// Forwards to `inner`, but leaves [`Sprite::opaque_segment`] at its default.
+struct NotOpaque<T>(T);
+impl<T: Sprite<Rgba8>> Sprite<Rgba8> for NotOpaque<T> {
+	fn lines(&self, all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		self.0.lines(all_lines_range)
+	}
+
+	fn line_segment(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+	) -> Range<isize> {
+		self.0.line_segment(all_lines_range, line, line_span)
+	}
+
+	fn render(
+		&self,
+		all_lines_range: Option<Range<isize>>,
+		line: isize,
+		line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		self.0
+			.render(all_lines_range, line, line_span, segment, offset_bits, data)
+	}
+}
+
+/// Random rectangles, some of them opaque, front to back.
+fn sprites(random: &mut Random) -> Vec<(Position, Box<dyn Sprite<Rgba8>>)> {
+	let background = Solid::<Rgba8, _>::new(random.color());
+	(0..random.below(8) + 1)
+		.map(|_| {
+			let position = Position {
+				x: random.below(16) - 8,
+				y: random.below(4) - 2,
+			};
+			let (lines, columns) = (random.range(16), random.range(48));
+			let clip = ColorClip::<Rgba8, _, _, _>::new(
+				move |_| lines.clone(),
+				move |_, _, _| columns.clone(),
+				random.color(),
+			);
+			let sprite: Box<dyn Sprite<Rgba8>> = match random.below(4) {
+				0 => Box::new(clip.opacity(random.color()[3])),
+				1 => Box::new(clip.offset(Position { x: 3, y: -1 })),
+				_ => Box::new(clip),
+			};
+			(position, sprite)
+		})
+		.chain(Some((
+			Position { x: 0, y: 0 },
+			Box::new(background) as Box<dyn Sprite<Rgba8>>,
+		)))
+		.collect()
+}
+
+fn render<S: Sprite<Rgba8>>(
+	sprites: impl IntoIterator<Item = (Position, S)>,
+	line: isize,
+	segment_span: Range<isize>,
+) -> Vec<u8> {
+	let mut buffer = vec![0; segment_span.len() * 4];
+	render_segment(
+		&Some(0..16),
+		line,
+		0..40,
+		segment_span,
+		&mut buffer,
+		sprites,
+		None::<(Position, Box<dyn Effect<Rgba8>>)>,
+	);
+	buffer
+}
+
+#[test]
+fn opaque_sprites_hide_those_behind() {
+	let mut random = Random(0x5EED_0F0A_1234_5678);
+	for _ in 0..500 {
+		let sprites = sprites(&mut random);
+		for line in 0..16 {
+			let segment_span = random.range(40);
+			assert_eq!(
+				render(
+					sprites.iter().map(|(position, sprite)| (*position, sprite)),
+					line,
+					segment_span.clone(),
+				),
+				render(
+					sprites
+						.iter()
+						.map(|(position, sprite)| (*position, NotOpaque(sprite))),
+					line,
+					segment_span,
+				),
+			);
+		}
+	}
+}
@@ -0,0 +1,301 @@
+//! TrueType parsing must look up and outline glyphs correctly, and treat malformed tables as missing glyphs or errors.
+
+mod common;
+
+use common::font::{cmap_12_groups, composite_of, font_with_glyphs, square, u16s, u32s};
+use scanline::{
+	drawables::{OutlineText, Solid},
+	pixel_formats::RgbaNoPadding,
+	text::{GlyphCache, OutlineFont},
+	Sprite,
+};
+
+/// A composite glyph of `component`, moved right by `dx` font units.
+fn composite(component: u16, dx: i8) -> Vec<u8> {
+	composite_of(&[(component, dx)])
+}
+
+/// A font with 16 units per em and these glyphs:
+///
+/// 0. empty, advance 1
+/// 1. [`square`] of size 8, advance 10
+/// 2. [`composite`] of glyph 1, advance 20
+/// 3. composite of itself, advance 30
+/// 4. truncated, advance 40
+fn font(cmap: Vec<u8>) -> Vec<u8> {
+	font_with_square(cmap, 8)
+}
+
+/// Like [`font`], but with a differently sized square.
+fn font_with_square(cmap: Vec<u8>, size: i16) -> Vec<u8> {
+	font_with_glyphs(
+		cmap,
+		&[
+			Vec::new(),
+			square(size),
+			composite(1, 8),
+			composite(3, 0),
+			u16s(&[1, 0, 0, 8, 8]),
+		],
+	)
+}
+
+/// Maps `A..=D` to glyphs 1 to 4 by delta, and `a` to glyph 1 and `b` to glyph 0 by glyph ID array.
+fn cmap_4() -> Vec<u8> {
+	let mut subtable = u16s(&[4, 0, 0, 6, 0, 0, 0]);
+	subtable.extend(u16s(&[68, 98, 0xFFFF, 0]));
+	subtable.extend(u16s(&[65, 97, 0xFFFF]));
+	subtable.extend(u16s(&[(1_u16).wrapping_sub(65), 0, 1]));
+	subtable.extend(u16s(&[0, 4, 0]));
+	subtable.extend(u16s(&[1, 0]));
+
+	let mut cmap = u16s(&[0, 1, 3, 1]);
+	cmap.extend(u32s(&[12]));
+	cmap.extend(subtable);
+	cmap
+}
+
+/// Maps `A` to glyph 1 and U+1F600 to glyph 2, with groups past the `u16` and `u32` glyph ranges and a bogus group count.
+fn cmap_12(group_count: u32) -> Vec<u8> {
+	cmap_12_groups(
+		group_count,
+		&[
+			[65, 65, 1],
+			[0x1F600, 0x1F600, 2],
+			[0x20000, 0x20001, u32::MAX],
+			[0x30000, 0x30000, 0x1_0001],
+		],
+	)
+}
+
+/// Renders `text` at 16 pixels per em and lists the covered pixels.
+fn covered(font: &OutlineFont<'_>, text: &str) -> Vec<(isize, isize)> {
+	covered_text(&OutlineText::new(
+		font,
+		16.,
+		text,
+		Solid::<RgbaNoPadding<8>, _>::new([255, 255, 255, 255]),
+	))
+}
+
+/// Like [`covered`], but with glyphs from `cache`.
+fn covered_cached(cache: &GlyphCache, font: &OutlineFont<'_>, text: &str) -> Vec<(isize, isize)> {
+	covered_text(&OutlineText::cached(
+		cache,
+		font,
+		16.,
+		text,
+		Solid::<RgbaNoPadding<8>, _>::new([255, 255, 255, 255]),
+	))
+}
+
+fn covered_text<F: Sprite<RgbaNoPadding<8>>>(text: &OutlineText<F>) -> Vec<(isize, isize)> {
+	let mut covered = Vec::new();
+	for line in Sprite::lines(text, None) {
+		let segment = Sprite::line_segment(text, None, line, 0..64);
+		let segment = segment.start.max(0)..segment.end.min(64);
+		if segment.is_empty() {
+			continue;
+		}
+		let mut data = vec![0; segment.len() * 4];
+		Sprite::render(text, None, line, 0..64, segment.clone(), 0, &mut data);
+		covered.extend(
+			segment
+				.zip(data.chunks_exact(4))
+				.filter(|(_, pixel)| pixel[3] > 127)
+				.map(|(x, _)| (x, line)),
+		);
+	}
+	covered
+}
+
+fn square_pixels(dx: isize) -> Vec<(isize, isize)> {
+	sized_square_pixels(dx, 8)
+}
+
+fn sized_square_pixels(dx: isize, size: isize) -> Vec<(isize, isize)> {
+	let mut pixels: Vec<_> = (12 - size..12)
+		.flat_map(|y| (dx..dx + size).map(move |x| (x, y)))
+		.collect();
+	pixels.sort_by_key(|&(x, y)| (y, x));
+	pixels
+}
+
+#[test]
+fn metrics() {
+	let data = font(cmap_4());
+	let font = OutlineFont::new(&data).unwrap();
+	assert_eq!(font.units_per_em(), 16);
+	assert_eq!(font.ascent(32.), 24.);
+	assert_eq!(font.line_height(32.), 32.);
+	assert_eq!(font.data(), &data[..]);
+}
+
+#[test]
+fn cmap_format_4() {
+	let data = font(cmap_4());
+	let font = OutlineFont::new(&data).unwrap();
+	for &(character, advance) in &[
+		('A', 10.),
+		('B', 20.),
+		('C', 30.),
+		('D', 40.),
+		('a', 10.),
+		// Mapped to glyph 0 or not at all.
+		('b', 1.),
+		('E', 1.),
+		('@', 1.),
+		('\u{1F600}', 1.),
+	] {
+		assert_eq!(font.advance(character, 16.), advance, "{:?}", character);
+	}
+}
+
+#[test]
+fn cmap_format_12() {
+	// The declared group count must neither be searched past the subtable, nor panic.
+	for &group_count in &[4, 3, u32::MAX] {
+		let data = font(cmap_12(group_count));
+		let font = OutlineFont::new(&data).unwrap();
+		assert_eq!(font.advance('A', 16.), 10.);
+		assert_eq!(font.advance('\u{1F600}', 16.), 20.);
+		assert_eq!(font.advance('Z', 16.), 1.);
+		// Glyph IDs that overflow `u32`, don't fit `u16` or exceed the glyph count are missing.
+		assert_eq!(font.advance('\u{20000}', 16.), 1.);
+		assert_eq!(font.advance('\u{20001}', 16.), 1.);
+		assert_eq!(font.advance('\u{30000}', 16.), 1.);
+	}
+}
+
+#[test]
+fn outlines() {
+	let data = font(cmap_4());
+	let font = OutlineFont::new(&data).unwrap();
+	assert_eq!(covered(&font, "A"), square_pixels(0));
+	assert_eq!(covered(&font, "a"), square_pixels(0));
+	// The composite is offset by 8 units.
+	assert_eq!(covered(&font, "B"), square_pixels(8));
+	// Recursive and truncated glyphs are empty.
+	assert_eq!(covered(&font, "C"), []);
+	assert_eq!(covered(&font, "D"), []);
+	// Advances accumulate, also over empty glyphs.
+	let mut expected = square_pixels(40);
+	expected.extend(square_pixels(50));
+	expected.sort_by_key(|&(x, y)| (y, x));
+	assert_eq!(covered(&font, "DAA"), expected);
+}
+
+#[test]
+fn composite_component_budget() {
+	// Glyphs 2 to 7 each repeat the next glyph 16 times, and glyph 8 repeats the square.
+	let mut glyphs = vec![Vec::new(), square(8)];
+	for glyph in 2..=8 {
+		let component = if glyph == 8 { 1 } else { glyph + 1 };
+		glyphs.push(composite_of(&[(component, 0); 16]));
+	}
+	let data = font_with_glyphs(cmap_12_groups(2, &[[65, 65, 2], [66, 66, 7]]), &glyphs);
+	let font = OutlineFont::new(&data).unwrap();
+
+	// 16 to the power of 7 squares are far too many, so the glyph is empty.
+	assert_eq!(font.advance('A', 16.), 20.);
+	assert_eq!(covered(&font, "A"), []);
+	// 16 + 16 × 16 components are fine.
+	assert_eq!(covered(&font, "B"), square_pixels(0));
+}
+
+#[test]
+fn malformed_tables() {
+	let error = |data: &[u8]| OutlineFont::new(data).unwrap_err().to_string();
+
+	assert_eq!(error(b""), "invalid font data: unrecognised font format");
+	assert_eq!(
+		error(b"OTTO\0\0\0\0"),
+		"invalid font data: CFF outlines are not supported"
+	);
+	assert_eq!(
+		error(&[0, 1, 0, 0]),
+		"invalid font data: truncated or invalid font tables"
+	);
+
+	let valid = font(cmap_4());
+	for length in &[6, 12, 12 + 7 * 16, valid.len() - 1] {
+		assert_eq!(
+			error(&valid[..*length]),
+			"invalid font data: truncated or invalid font tables",
+			"{}",
+			length
+		);
+	}
+
+	let mut missing_glyf = valid.clone();
+	missing_glyf[12 + 16..12 + 20].copy_from_slice(b"gly_");
+	assert_eq!(
+		error(&missing_glyf),
+		"invalid font data: missing `glyf` table"
+	);
+
+	let mut huge_offset = valid.clone();
+	huge_offset[12 + 8..12 + 12].copy_from_slice(&u32::MAX.to_be_bytes());
+	assert_eq!(
+		error(&huge_offset),
+		"invalid font data: truncated or invalid font tables"
+	);
+}
+
+#[test]
+fn truncated_cmap_subtables() {
+	let cmap = cmap_4();
+	for length in 0..cmap.len() {
+		let data = font(cmap[..length].to_vec());
+		let font = OutlineFont::new(&data).unwrap();
+		let advance = font.advance('A', 16.);
+		assert!(advance == 10. || advance == 1., "{}: {}", length, advance);
+	}
+
+	let cmap = cmap_12(u32::MAX);
+	for length in 0..cmap.len() {
+		let data = font(cmap[..length].to_vec());
+		let font = OutlineFont::new(&data).unwrap();
+		assert_eq!(
+			font.advance('\u{1F600}', 16.),
+			// Until the second group is complete.
+			if length >= 12 + 16 + 2 * 12 { 20. } else { 1. },
+			"{}",
+			length
+		);
+	}
+}
+
+#[test]
+fn glyph_cache_distinguishes_fonts() {
+	let cache = GlyphCache::new(1 << 20);
+	let mut data = font(cmap_4());
+	{
+		let font = OutlineFont::new(&data).unwrap();
+		assert_eq!(covered_cached(&cache, &font, "A"), square_pixels(0));
+		assert_eq!(covered_cached(&cache, &font, "A"), square_pixels(0));
+		assert_eq!(cache.len(), 1);
+	}
+
+	// A different font at the same address with the same length.
+	let other = font_with_square(cmap_4(), 4);
+	assert_eq!(other.len(), data.len());
+	data.copy_from_slice(&other);
+	let font = OutlineFont::new(&data).unwrap();
+	assert_eq!(covered(&font, "A"), sized_square_pixels(0, 4));
+	assert_eq!(
+		covered_cached(&cache, &font, "A"),
+		sized_square_pixels(0, 4)
+	);
+	assert_eq!(cache.len(), 2);
+
+	// Copies share their glyphs.
+	let copy = font;
+	assert_eq!(covered_cached(&cache, &copy, "AB"), {
+		let mut expected = sized_square_pixels(0, 4);
+		expected.extend(sized_square_pixels(18, 4));
+		expected.sort_by_key(|&(x, y)| (y, x));
+		expected
+	});
+	assert_eq!(cache.len(), 3);
+}
@@ -0,0 +1,58 @@
+//! Parallel rendering must produce exactly the same bytes as sequential rendering.
+#![cfg(feature = "rayon")]
+
+mod common;
+
+use common::{scene, Random};
+use rayon::ThreadPoolBuilder;
+use scanline::canvas::Canvas;
+
+#[test]
+fn par_render_equals_render() {
+	let mut random = Random(0x0123_4567_89AB_CDEF);
+	for &threads in &[1, 2, 3, 8] {
+		let pool = ThreadPoolBuilder::new()
+			.num_threads(threads)
+			.build()
+			.unwrap();
+		for &(width, height, stride) in &[(97, 61, 97 * 4), (16, 1, 80), (3, 200, 12)] {
+			let scene = scene(&mut random, 20);
+
+			let mut expected = Canvas::with_stride(width, height, stride);
+			expected.render(&scene);
+			let mut canvas = Canvas::with_stride(width, height, stride);
+			canvas.data_mut().fill(0xAB);
+			pool.install(|| canvas.par_render(&scene));
+			// Padding between rows is left alone.
+			for line in 0..height {
+				assert_eq!(canvas.row(line), expected.row(line), "{} threads", threads);
+			}
+		}
+	}
+}
+
+#[test]
+fn par_render_rect_equals_render_rect() {
+	let mut random = Random(0xFEDC_BA98_7654_3210);
+	let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+	for _ in 0..20 {
+		let scene = scene(&mut random, 20);
+		let mut expected = Canvas::new(50, 70);
+		expected.data_mut().fill(0x55);
+		let mut canvas = Canvas::new(50, 70);
+		canvas.data_mut().fill(0x55);
+
+		let top = random.below(70) as usize;
+		let lines = top..top + random.below((70 - top) as u64 + 1) as usize;
+		let left = random.below(50) as usize;
+		let columns = left..left + random.below((50 - left) as u64 + 1) as usize;
+		expected.render_rect(&scene, lines.clone(), columns.clone());
+		pool.install(|| canvas.par_render_rect(&scene, lines.clone(), columns.clone()));
+		assert!(
+			canvas.data() == expected.data(),
+			"{:?} {:?}",
+			lines,
+			columns
+		);
+	}
+}
@@ -0,0 +1,141 @@
+//! Segments not starting at column `0` must be rendered into buffers holding only that segment.
+
+use scanline::{
+	combinators::SpriteExt,
+	drawables::{ColorClip, Solid},
+	pixel_formats::RgbaNoPadding,
+	render_segment, Effect, PixelFormat, Position, Sprite,
+};
+use std::{convert::TryInto, ops::Range};
+
+type Rgba8 = RgbaNoPadding<8>;
+
+fn render_rgba8(segment_span: Range<isize>) -> Vec<u8> {
+	let sprites: Vec<(Position, Box<dyn Sprite<Rgba8>>)> = vec![
+		(
+			Position { x: 3, y: 0 },
+			Box::new(ColorClip::<Rgba8, _, _, _>::new(
+				|_| 0..1,
+				|_, _, _| 2..9,
+				[0, 128, 0, 128],
+			)),
+		),
+		(
+			Position { x: 0, y: 0 },
+			Box::new(Solid::<Rgba8, _>::new([10, 20, 30, 255]).clip(0..1, 4..20)),
+		),
+	];
+	let effects: Vec<(Position, Box<dyn Effect<Rgba8>>)> = vec![(
+		Position { x: -2, y: 0 },
+		Box::new(ColorClip::<Rgba8, _, _, _>::new(
+			|_| 0..1,
+			|_, _, _| 5..14,
+			[100, 0, 0, 100],
+		)),
+	)];
+
+	let mut buffer = vec![0; segment_span.len() * 4];
+	render_segment(
+		&Some(0..1),
+		0,
+		0..24,
+		segment_span,
+		&mut buffer,
+		sprites,
+		effects,
+	);
+	buffer
+}
+
+#[test]
+fn rgba8_segments() {
+	let line = render_rgba8(0..24);
+	assert_ne!(line, vec![0; 24 * 4]);
+	for start in 0..24 {
+		for end in start..=24 {
+			assert_eq!(
+				render_rgba8(start..end),
+				&line[start as usize * 4..end as usize * 4],
+				"{}..{}",
+				start,
+				end
+			);
+		}
+	}
+}
+
+/// Four bits per pixel, with the first pixel of each byte in its low nibble.
+struct Nibbles;
+impl PixelFormat for Nibbles {
+	const PIXEL_STRIDE_BITS: usize = 4;
+}
+
+/// Writes each pixel's column, modulo 16.
+struct Columns;
+impl Sprite<Nibbles> for Columns {
+	fn lines(&self, _all_lines_range: Option<Range<isize>>) -> Range<isize> {
+		0..1
+	}
+
+	fn line_segment(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		_line_span: Range<isize>,
+	) -> Range<isize> {
+		2..13
+	}
+
+	fn render(
+		&self,
+		_all_lines_range: Option<Range<isize>>,
+		_line: isize,
+		_line_span: Range<isize>,
+		segment: Range<isize>,
+		offset_bits: usize,
+		data: &mut [u8],
+	) {
+		for (i, x) in segment.enumerate() {
+			let bit = offset_bits + i * 4;
+			let nibble: u8 = (x % 16).try_into().unwrap();
+			data[bit / 8] |= nibble << (bit % 8);
+		}
+	}
+}
+
+fn render_nibbles(segment_span: Range<isize>) -> Vec<u8> {
+	let offset_bits = (segment_span.start % 2 * 4) as usize;
+	#[allow(clippy::manual_div_ceil)]
+	let mut buffer = vec![0; (offset_bits + segment_span.len() * 4 + 7) / 8];
+	render_segment(
+		&Some(0..1),
+		0,
+		0..16,
+		segment_span.clone(),
+		&mut buffer,
+		Some((Position { x: 1, y: 0 }, Columns)),
+		None::<(Position, Box<dyn Effect<Nibbles>>)>,
+	);
+
+	// Unpacks the segment's pixels.
+	(0..segment_span.len())
+		.map(|i| buffer[(offset_bits + i * 4) / 8] >> ((offset_bits + i * 4) % 8) & 0xF)
+		.collect()
+}
+
+#[test]
+fn sub_byte_segments() {
+	let line = render_nibbles(0..16);
+	assert_eq!(line, [0, 0, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 0, 0]);
+	for start in 0..16 {
+		for end in start..=16 {
+			assert_eq!(
+				render_nibbles(start..end),
+				&line[start as usize..end as usize],
+				"{}..{}",
+				start,
+				end
+			);
+		}
+	}
+}
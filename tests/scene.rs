@@ -0,0 +1,243 @@
+//! Scenes must draw in z-order, keep handles valid exactly until their drawable is removed,
+//! and render the same as without their line index.
+
+mod common;
+
+use common::{Random, Rect, Rgba8};
+use scanline::{
+	combinators::SpriteExt,
+	drawables::Solid,
+	render_segment,
+	scene::{EffectHandle, Scene, SpriteHandle},
+	Position,
+};
+use std::{cmp::Reverse, ops::Range};
+
+const ORIGIN: Position = Position { x: 0, y: 0 };
+const RED: [u8; 4] = [255, 0, 0, 255];
+const GREEN: [u8; 4] = [0, 255, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+/// An opaque pixel at the origin.
+fn pixel(color: [u8; 4]) -> Box<Rect> {
+	Box::new(Solid::new(color).clip(0..1, 0..1))
+}
+
+fn render(scene: &Scene<Rgba8, Rect, Rect>) -> [u8; 4] {
+	let mut buffer = [0; 4];
+	scene.render_line(0, &mut buffer);
+	buffer
+}
+
+#[test]
+fn sprite_z_order() {
+	let mut scene = Scene::<Rgba8, Rect, Rect>::new();
+	let red = scene.insert_sprite(pixel(RED), ORIGIN, 1);
+	let green = scene.insert_sprite(pixel(GREEN), ORIGIN, 0);
+	assert_eq!(render(&scene), RED);
+
+	// Later insertions are in front among equal `z`.
+	let blue = scene.insert_sprite(pixel(BLUE), ORIGIN, 1);
+	assert_eq!(render(&scene), BLUE);
+
+	assert_eq!(scene.set_sprite_z(blue, -1), Some(1));
+	assert_eq!(render(&scene), RED);
+
+	// Changing `z` moves sprites in front of others with the same one, even if it didn't change.
+	assert_eq!(scene.set_sprite_z(green, 1), Some(0));
+	assert_eq!(render(&scene), GREEN);
+	assert_eq!(scene.set_sprite_z(red, 1), Some(1));
+	assert_eq!(render(&scene), RED);
+}
+
+#[test]
+fn effect_z_order() {
+	let mut scene = Scene::<Rgba8, Rect, Rect>::new();
+	let red = scene.insert_effect(pixel(RED), ORIGIN, 0);
+	let blue = scene.insert_effect(pixel(BLUE), ORIGIN, 0);
+	assert_eq!(render(&scene), BLUE);
+
+	assert_eq!(scene.set_effect_z(blue, -1), Some(0));
+	assert_eq!(render(&scene), RED);
+	assert_eq!(scene.set_effect_z(blue, 0), Some(-1));
+	assert_eq!(render(&scene), BLUE);
+	assert_eq!(scene.set_effect_z(red, 0), Some(0));
+	assert_eq!(render(&scene), RED);
+
+	// All sprites are below all effects.
+	scene.insert_sprite(pixel(GREEN), ORIGIN, i32::MAX);
+	assert_eq!(render(&scene), RED);
+}
+
+#[test]
+fn removal_invalidates_handles() {
+	let mut scene = Scene::<Rgba8, Rect, Rect>::new();
+	let red = scene.insert_sprite(pixel(RED), ORIGIN, 0);
+	let green = scene.insert_sprite(pixel(GREEN), ORIGIN, 0);
+	let blue = scene.insert_effect(pixel(BLUE), ORIGIN, 0);
+	assert_eq!((scene.sprite_count(), scene.effect_count()), (2, 1));
+
+	assert_eq!(scene.remove_effect(blue), Some(pixel(BLUE)));
+	assert_eq!(render(&scene), GREEN);
+	assert_eq!(scene.remove_sprite(green), Some(pixel(GREEN)));
+	assert_eq!(render(&scene), RED);
+	assert_eq!((scene.sprite_count(), scene.effect_count()), (1, 0));
+
+	// Removed handles are dead for good, even after further insertions.
+	let new_green = scene.insert_sprite(pixel(GREEN), ORIGIN, 0);
+	let new_blue = scene.insert_effect(pixel(BLUE), ORIGIN, 0);
+	assert_ne!(new_green, green);
+	assert_ne!(new_blue, blue);
+	assert_eq!(scene.remove_sprite(green), None);
+	assert!(scene.sprite(green).is_none());
+	assert!(scene.sprite_mut(green).is_none());
+	assert_eq!(scene.sprite_position(green), None);
+	assert_eq!(scene.move_sprite(green, ORIGIN), None);
+	assert_eq!(scene.set_sprite_z(green, 1), None);
+	assert_eq!(scene.remove_effect(blue), None);
+	assert!(scene.effect(blue).is_none());
+	assert!(scene.effect_mut(blue).is_none());
+	assert_eq!(scene.effect_position(blue), None);
+	assert_eq!(scene.move_effect(blue, ORIGIN), None);
+	assert_eq!(scene.set_effect_z(blue, 1), None);
+	assert_eq!((scene.sprite_count(), scene.effect_count()), (2, 1));
+
+	// Live handles still address the same drawables.
+	assert_eq!(scene.sprite(red), Some(&*pixel(RED)));
+	assert_eq!(scene.sprite(new_green), Some(&*pixel(GREEN)));
+	assert_eq!(
+		scene.move_sprite(red, Position { x: 1, y: 0 }),
+		Some(ORIGIN)
+	);
+	assert_eq!(scene.sprite_position(red), Some(Position { x: 1, y: 0 }));
+	assert_eq!(render(&scene), BLUE);
+}
+
+/// Drawables as inserted into a scene, in insertion order.
+#[derive(Default)]
+struct Drawables {
+	sprites: Vec<(SpriteHandle, Rect, Position, i32)>,
+	effects: Vec<(EffectHandle, Rect, Position, i32)>,
+}
+impl Drawables {
+	fn sprite(
+		&mut self,
+		scene: &mut Scene<Rgba8, Rect, Rect>,
+		rect: Rect,
+		position: Position,
+		z: i32,
+	) {
+		let handle = scene.insert_sprite(Box::new(rect.clone()), position, z);
+		self.sprites.push((handle, rect, position, z));
+	}
+
+	fn effect(
+		&mut self,
+		scene: &mut Scene<Rgba8, Rect, Rect>,
+		rect: Rect,
+		position: Position,
+		z: i32,
+	) {
+		let handle = scene.insert_effect(Box::new(rect.clone()), position, z);
+		self.effects.push((handle, rect, position, z));
+	}
+
+	/// Renders all drawables without an index, sorted as documented for [`Scene`].
+	fn render(
+		&self,
+		all_lines_range: &Option<Range<isize>>,
+		line: isize,
+		segment_span: Range<isize>,
+	) -> Vec<u8> {
+		let mut sprites: Vec<_> = self.sprites.iter().enumerate().collect();
+		sprites.sort_by_key(|(i, (_, _, _, z))| Reverse((*z, *i)));
+		let mut effects: Vec<_> = self.effects.iter().enumerate().collect();
+		effects.sort_by_key(|(i, (_, _, _, z))| (*z, *i));
+
+		let mut buffer = vec![0; segment_span.len() * 4];
+		render_segment(
+			all_lines_range,
+			line,
+			0..70,
+			segment_span,
+			&mut buffer,
+			sprites
+				.into_iter()
+				.map(|(_, (_, rect, position, _))| (*position, Box::new(rect.clone()))),
+			effects
+				.into_iter()
+				.map(|(_, (_, rect, position, _))| (*position, Box::new(rect.clone()))),
+		);
+		buffer
+	}
+}
+
+fn assert_matches_brute_force(
+	scene: &Scene<Rgba8, Rect, Rect>,
+	drawables: &Drawables,
+	random: &mut Random,
+) {
+	for all_lines_range in [None, Some(-20..80), Some(10..30)] {
+		for line in -20..80 {
+			let segment_span = random.range(70);
+			let mut buffer = vec![0; segment_span.len() * 4];
+			scene.render_segment(
+				&all_lines_range,
+				line,
+				0..70,
+				segment_span.clone(),
+				&mut buffer,
+			);
+			assert!(
+				buffer == drawables.render(&all_lines_range, line, segment_span.clone()),
+				"{:?} {} {:?}",
+				all_lines_range,
+				line,
+				segment_span
+			);
+		}
+	}
+}
+
+#[test]
+fn index_matches_brute_force() {
+	let mut random = Random(43);
+	let mut scene = Scene::<Rgba8, Rect, Rect>::new();
+	let mut drawables = Drawables::default();
+	for i in 0..60 {
+		let (rect, position, z) = (
+			*random.rect(60, 60),
+			random.position(10),
+			random.below(5) as i32,
+		);
+		if i % 3 == 0 {
+			drawables.effect(&mut scene, rect, position, z);
+		} else {
+			drawables.sprite(&mut scene, rect, position, z);
+		}
+	}
+	// Tall enough to skip the buckets.
+	let tall =
+		|random: &mut Random, lines| Solid::new(random.color()).clip(lines, random.range(60));
+	let rect = tall(&mut random, isize::MIN..isize::MAX);
+	drawables.sprite(&mut scene, rect, ORIGIN, 2);
+	let rect = tall(&mut random, -100_000..100_000);
+	drawables.effect(&mut scene, rect, Position { x: 3, y: 7 }, 2);
+	assert_matches_brute_force(&scene, &drawables, &mut random);
+
+	// Changes must update the index.
+	// Setting `z` moves sprites in front of others with the same one, like a new insertion.
+	for _ in 0..10 {
+		let i = random.below(drawables.sprites.len() as u64) as usize;
+		let (handle, rect, ..) = drawables.sprites.remove(i);
+		let (position, z) = (random.position(20), random.below(5) as i32);
+		scene.move_sprite(handle, position);
+		scene.set_sprite_z(handle, z);
+		drawables.sprites.push((handle, rect, position, z));
+	}
+	let (handle, ..) = drawables.sprites.remove(5);
+	scene.remove_sprite(handle);
+	let (handle, ..) = drawables.effects.remove(2);
+	scene.remove_effect(handle);
+	assert_matches_brute_force(&scene, &drawables, &mut random);
+}
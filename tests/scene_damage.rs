@@ -0,0 +1,136 @@
+//! Incremental rendering must always match rendering the whole scene again.
+
+mod common;
+
+use common::{Random, Rect, Rgba8};
+use scanline::{
+	canvas::Canvas,
+	combinators::SpriteExt,
+	drawables::Solid,
+	scene::{EffectHandle, Scene, SpriteHandle},
+	Position,
+};
+use std::convert::TryInto;
+
+fn assert_fresh(scene: &mut Scene<Rgba8, Rect, Rect>, canvas: &mut Canvas<Rgba8>) {
+	scene.render_damage(canvas);
+	let mut fresh = Canvas::new(canvas.width(), canvas.height());
+	fresh.render(scene);
+	assert!(canvas.data() == fresh.data());
+}
+
+#[test]
+fn move_remove_and_reorder() {
+	let mut scene = Scene::<Rgba8, Rect, Rect>::new();
+	let mut canvas = Canvas::new(32, 24);
+	let rect = |color, lines, columns| Box::new(Solid::new(color).clip(lines, columns));
+
+	scene.insert_sprite(
+		rect([0, 0, 255, 255], 0..24, 0..32),
+		Position { x: 0, y: 0 },
+		0,
+	);
+	let red = scene.insert_sprite(
+		rect([255, 0, 0, 255], 0..8, 0..8),
+		Position { x: 2, y: 2 },
+		1,
+	);
+	let green = scene.insert_sprite(
+		rect([0, 128, 0, 128], 0..8, 0..8),
+		Position { x: 6, y: 6 },
+		2,
+	);
+	let effect = scene.insert_effect(
+		rect([40, 40, 40, 40], 0..4, 0..32),
+		Position { x: 0, y: 10 },
+		0,
+	);
+	assert_fresh(&mut scene, &mut canvas);
+
+	scene.move_sprite(red, Position { x: 20, y: 12 });
+	assert_fresh(&mut scene, &mut canvas);
+
+	scene.set_sprite_z(red, 3);
+	scene.move_sprite(green, Position { x: 18, y: 10 });
+	assert_fresh(&mut scene, &mut canvas);
+
+	scene.set_sprite_z(red, -1);
+	assert_fresh(&mut scene, &mut canvas);
+
+	scene.remove_sprite(green);
+	assert_fresh(&mut scene, &mut canvas);
+
+	scene.move_effect(effect, Position { x: 4, y: 0 });
+	scene.remove_sprite(red);
+	assert_fresh(&mut scene, &mut canvas);
+
+	// Moves partially and entirely off the canvas.
+	let moving = scene.insert_sprite(
+		rect([9, 9, 9, 255], 0..8, 0..8),
+		Position { x: 28, y: 20 },
+		5,
+	);
+	assert_fresh(&mut scene, &mut canvas);
+	scene.move_sprite(moving, Position { x: -100, y: 5 });
+	assert_fresh(&mut scene, &mut canvas);
+	scene.move_sprite(moving, Position { x: 0, y: 0 });
+	assert_fresh(&mut scene, &mut canvas);
+
+	// Size changes render everything again.
+	let mut smaller = Canvas::new(10, 30);
+	assert_fresh(&mut scene, &mut smaller);
+}
+
+#[test]
+fn random_changes() {
+	let mut random = Random(0xDA3A_6E5E_ED00_0001);
+	let mut scene = Scene::<Rgba8, Rect, Rect>::new();
+	let mut canvas = Canvas::new(40, 30);
+	let mut sprites: Vec<SpriteHandle> = Vec::new();
+	let mut effects: Vec<EffectHandle> = Vec::new();
+
+	for _ in 0..400 {
+		for _ in 0..random.below(3) + 1 {
+			let pick = |random: &mut Random, count: usize| {
+				random.below(count.try_into().unwrap()) as usize
+			};
+			match random.below(9) {
+				0 | 1 => {
+					let z = random.below(5) as i32;
+					sprites.push(scene.insert_sprite(random.rect(20, 30), random.position(20), z))
+				}
+				2 => {
+					let z = random.below(5) as i32;
+					effects.push(scene.insert_effect(random.rect(20, 30), random.position(20), z))
+				}
+				3 if !sprites.is_empty() => {
+					let handle = sprites.swap_remove(pick(&mut random, sprites.len()));
+					assert!(scene.remove_sprite(handle).is_some());
+				}
+				4 if !effects.is_empty() => {
+					let handle = effects.swap_remove(pick(&mut random, effects.len()));
+					assert!(scene.remove_effect(handle).is_some());
+				}
+				5 if !sprites.is_empty() => {
+					let handle = sprites[pick(&mut random, sprites.len())];
+					scene.move_sprite(handle, random.position(20));
+				}
+				6 if !sprites.is_empty() => {
+					let handle = sprites[pick(&mut random, sprites.len())];
+					scene.set_sprite_z(handle, random.below(5) as i32);
+				}
+				7 if !effects.is_empty() => {
+					let handle = effects[pick(&mut random, effects.len())];
+					scene.move_effect(handle, random.position(20));
+					scene.set_effect_z(handle, random.below(5) as i32);
+				}
+				8 if !sprites.is_empty() => {
+					let handle = sprites[pick(&mut random, sprites.len())];
+					*scene.sprite_mut(handle).unwrap() = *random.rect(20, 30);
+				}
+				_ => (),
+			}
+		}
+		assert_fresh(&mut scene, &mut canvas);
+	}
+}
@@ -0,0 +1,99 @@
+//! Shaders must be called with the coordinates of exactly the pixels and segments they render.
+
+mod common;
+
+use common::Rgba8;
+use scanline::{
+	drawables::{PixelShader, SegmentShader},
+	render_segment, Effect, Position, Sprite,
+};
+use std::{cell::RefCell, ops::Range};
+
+fn lines(_: Option<Range<isize>>) -> Range<isize> {
+	-2..3
+}
+
+fn segments(_: Option<Range<isize>>, _: isize, line_span: Range<isize>) -> Range<isize> {
+	line_span
+}
+
+/// Encodes `x` and `y` around `-128` into an opaque colour.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn coordinates(x: isize, y: isize) -> [u8; 4] {
+	[(x + 128) as u8, (y + 128) as u8, 0, 255]
+}
+
+#[test]
+fn pixel_shader_coordinates() {
+	let shader = PixelShader::<Rgba8, _, _, _>::new(lines, segments, coordinates);
+	assert_eq!(Sprite::lines(&shader, None), -2..3);
+	assert_eq!(Sprite::line_segment(&shader, None, 0, -5..7), -5..7);
+
+	let mut data = vec![0; 4 * 4];
+	Sprite::render(&shader, Some(-2..3), -1, -5..7, -3..1, 0, &mut data);
+	let expected: Vec<u8> = (-3..1).flat_map(|x| coordinates(x, -1)).collect();
+	assert_eq!(data, expected);
+
+	// Long segments are shaded in chunks.
+	let mut data = vec![0; 150 * 4];
+	Sprite::render(&shader, Some(-2..3), 2, -100..100, -70..80, 0, &mut data);
+	let long: Vec<u8> = (-70..80).flat_map(|x| coordinates(x, 2)).collect();
+	assert_eq!(data, long);
+
+	// Effects blend the same pixels over what is below.
+	let mut data = vec![9; 4 * 4];
+	Effect::render(&shader, Some(-2..3), -1, -5..7, -3..1, 0, &mut data);
+	assert_eq!(data, expected);
+
+	// Positions translate the shader's coordinates, and segments render only their own pixels.
+	let position = Position { x: 10, y: 20 };
+	for segment_span in [10..14, 11..13, 13..14] {
+		let mut buffer = vec![0; segment_span.len() * 4];
+		render_segment(
+			&Some(0..40),
+			21,
+			0..40,
+			segment_span.clone(),
+			&mut buffer,
+			Some((position, shader)),
+			None::<(Position, Box<dyn Effect<Rgba8>>)>,
+		);
+		let expected: Vec<u8> = segment_span
+			.clone()
+			.flat_map(|x| coordinates(x - 10, 1))
+			.collect();
+		assert_eq!(buffer, expected, "{:?}", segment_span);
+	}
+}
+
+#[test]
+fn segment_shader_segments() {
+	let calls = RefCell::new(Vec::new());
+	let shader = SegmentShader::<Rgba8, _, _, _>::new(
+		lines,
+		segments,
+		|line: isize, segment: Range<isize>, data: &mut [u8]| {
+			// The scratch buffer starts out transparent and holds exactly the segment.
+			assert!(data.iter().all(|channel| *channel == 0));
+			assert_eq!(data.len(), segment.len() * 4);
+			for (x, pixel) in segment.clone().zip(data.chunks_exact_mut(4)) {
+				pixel.copy_from_slice(&coordinates(x, line));
+			}
+			calls.borrow_mut().push((line, segment));
+		},
+	);
+
+	// Sprites render behind opaque pixels, but are still shaded.
+	let mut data = [7, 7, 7, 255].repeat(5);
+	Sprite::render(&shader, Some(-2..3), 2, -8..8, -4..1, 0, &mut data);
+	assert_eq!(data, [7, 7, 7, 255].repeat(5));
+	let expected: Vec<u8> = (-4..1).flat_map(|x| coordinates(x, 2)).collect();
+	let mut data = vec![0; 5 * 4];
+	Sprite::render(&shader, Some(-2..3), 2, -8..8, -4..1, 0, &mut data);
+	assert_eq!(data, expected);
+	let mut data = vec![7; 5 * 4];
+	Effect::render(&shader, Some(-2..3), 2, -8..8, -4..1, 0, &mut data);
+	assert_eq!(data, expected);
+
+	assert_eq!(*calls.borrow(), [(2, -4..1), (2, -4..1), (2, -4..1)]);
+}
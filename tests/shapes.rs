@@ -0,0 +1,166 @@
+//! Shapes must cover the pixels inside their outline, with partial coverage along their edges.
+
+mod common;
+
+use common::{area, Rgba8};
+use scanline::{
+	drawables::{Circle, Ellipse, RoundedRect, Solid},
+	geometry::Point,
+	Sprite,
+};
+
+const SIZE: usize = 16;
+
+fn paint() -> Solid<Rgba8, [u8; 4]> {
+	Solid::new([255; 4])
+}
+
+/// Renders the whole `SIZE`×`SIZE` square.
+fn alphas(shape: &impl Sprite<Rgba8>) -> Vec<Vec<u8>> {
+	common::alphas(shape, SIZE, SIZE)
+}
+
+fn count(alphas: &[Vec<u8>], alpha: u8) -> usize {
+	alphas.iter().flatten().filter(|a| **a == alpha).count()
+}
+
+#[test]
+fn circle() {
+	let circle = Circle::new(Point::new(8., 8.), 4., paint());
+	assert_eq!(circle.lines(None), 4..12);
+	assert_eq!(circle.line_segment(None, 8, 0..16), 4..12);
+	assert_eq!(circle.line_segment(None, 4, 0..16), 5..11);
+
+	let alphas = alphas(&circle);
+	let expected = std::f32::consts::PI * 16.;
+	assert!((area(&alphas) - expected).abs() < 0.5, "{}", area(&alphas));
+	// Pixels are fully covered iff their outer corner is inside, which holds for 8 per quadrant.
+	assert_eq!(count(&alphas, 255), 4 * 8);
+	assert_eq!(alphas[0], [0; SIZE]);
+	assert_eq!(alphas[8][8], 255);
+
+	// Symmetric across both axes and the diagonal, except for sampling error.
+	for (y, line) in alphas.iter().enumerate() {
+		for (x, alpha) in line.iter().enumerate() {
+			for mirrored in &[
+				alphas[x][y],
+				alphas[y][SIZE - 1 - x],
+				alphas[SIZE - 1 - y][x],
+			] {
+				assert!((i16::from(*alpha) - i16::from(*mirrored)).abs() <= 2);
+			}
+		}
+	}
+}
+
+#[test]
+fn circle_edge_alpha() {
+	// The edge runs through the middle of pixel 12 on line 8, bending slightly inwards.
+	let alphas = alphas(&Circle::new(Point::new(8.5, 8.5), 4., paint()));
+	assert!((120..128).contains(&alphas[8][12]), "{}", alphas[8][12]);
+	assert_eq!(alphas[8][12], alphas[8][4]);
+	assert_eq!(alphas[8][11], 255);
+	assert_eq!(alphas[8][13], 0);
+}
+
+#[test]
+fn ellipse() {
+	let ellipse = Ellipse::new(Point::new(8., 8.), 6., 2., paint());
+	assert_eq!(ellipse.lines(None), 6..10);
+	assert_eq!(ellipse.line_segment(None, 7, 0..16), 2..14);
+
+	let alphas = alphas(&ellipse);
+	let expected = std::f32::consts::PI * 12.;
+	assert!((area(&alphas) - expected).abs() < 0.5, "{}", area(&alphas));
+	assert_eq!(alphas[5], [0; SIZE]);
+	assert_eq!(alphas[10], [0; SIZE]);
+	assert!((1..255).contains(&alphas[7][2]), "{}", alphas[7][2]);
+	assert_eq!(alphas[7][3..13], [255; 10]);
+	assert_eq!(alphas[7], alphas[8]);
+}
+
+#[test]
+fn rounded_rect() {
+	let square_corners = alphas(&RoundedRect::new(
+		Point::new(2., 2.),
+		Point::new(14., 10.),
+		0.,
+		paint(),
+	));
+	assert_eq!(count(&square_corners, 255), 12 * 8);
+	assert_eq!(count(&square_corners, 0), SIZE * SIZE - 12 * 8);
+
+	let rounded = alphas(&RoundedRect::new(
+		Point::new(2., 2.),
+		Point::new(14., 10.),
+		2.,
+		paint(),
+	));
+	let expected = 96. - (4. - std::f32::consts::PI) * 4.;
+	assert!(
+		(area(&rounded) - expected).abs() < 0.25,
+		"{}",
+		area(&rounded)
+	);
+	assert!((1..128).contains(&rounded[2][2]), "{}", rounded[2][2]);
+	assert_eq!(rounded[2][4..12], [255; 8]);
+	assert_eq!(rounded[4][2..14], [255; 12]);
+
+	// The radius is limited to half of the shorter side.
+	let clamped = alphas(&RoundedRect::new(
+		Point::new(2., 2.),
+		Point::new(14., 10.),
+		100.,
+		paint(),
+	));
+	let expected = 96. - (4. - std::f32::consts::PI) * 16.;
+	assert!(
+		(area(&clamped) - expected).abs() < 0.25,
+		"{}",
+		area(&clamped)
+	);
+	assert_eq!(clamped[6][3..13], [255; 10]);
+}
+
+#[test]
+fn half_pixel_edges() {
+	let alphas = alphas(&RoundedRect::new(
+		Point::new(2.5, 2.),
+		Point::new(13.5, 10.),
+		0.,
+		paint(),
+	));
+	assert_eq!(alphas[5][2], 128);
+	assert_eq!(alphas[5][3..13], [255; 10]);
+	assert_eq!(alphas[5][13], 128);
+}
+
+#[test]
+fn nan_outlines_cover_nothing() {
+	assert_eq!(
+		count(
+			&alphas(&Ellipse::new(Point::new(8., 8.), f32::NAN, 4., paint())),
+			0
+		),
+		SIZE * SIZE
+	);
+	assert_eq!(
+		count(
+			&alphas(&Circle::new(Point::new(f32::NAN, 8.), 4., paint())),
+			0
+		),
+		SIZE * SIZE
+	);
+	assert_eq!(
+		count(
+			&alphas(&RoundedRect::new(
+				Point::new(2., 2.),
+				Point::new(f32::NAN, 10.),
+				0.,
+				paint()
+			)),
+			0
+		),
+		SIZE * SIZE
+	);
+}
@@ -0,0 +1,202 @@
+//! Strokes must cover the documented area around their polyline, including caps and joins, and hairlines their Bresenham pixels.
+
+mod common;
+
+use common::{alphas, Rgba8};
+use scanline::{
+	drawables::{LineCap, LineJoin, Solid, Stroke, StrokeStyle},
+	geometry::Point,
+	Position, Sprite,
+};
+
+const SIZE: usize = 16;
+
+fn paint() -> Solid<Rgba8, [u8; 4]> {
+	Solid::new([255; 4])
+}
+
+fn style(cap: LineCap, join: LineJoin, miter_limit: f32) -> StrokeStyle {
+	StrokeStyle {
+		width: 4.,
+		cap,
+		join,
+		miter_limit,
+		..StrokeStyle::default()
+	}
+}
+
+/// A horizontal stroke 4 pixels wide, from x 4 to 12 on lines 6 to 10.
+fn capped(cap: LineCap) -> Vec<Vec<u8>> {
+	let stroke = Stroke::new(
+		vec![Point::new(4., 8.), Point::new(12., 8.)],
+		&style(cap, LineJoin::Miter, 4.),
+		paint(),
+	);
+	alphas(&stroke, SIZE, SIZE)
+}
+
+#[test]
+fn caps() {
+	let butt = capped(LineCap::Butt);
+	for (line, alphas) in butt.iter().enumerate() {
+		if (6..10).contains(&line) {
+			assert_eq!(alphas[4..12], [255; 8], "{}", line);
+			assert_eq!(alphas[..4], [0; 4], "{}", line);
+			assert_eq!(alphas[12..], [0; 4], "{}", line);
+		} else {
+			assert_eq!(*alphas, [0; SIZE], "{}", line);
+		}
+	}
+
+	// Square caps extend by half the width at both ends.
+	let square = capped(LineCap::Square);
+	for (line, alphas) in square.iter().enumerate() {
+		if (6..10).contains(&line) {
+			assert_eq!(alphas[2..14], [255; 12], "{}", line);
+			assert_eq!(alphas[..2], [0; 2], "{}", line);
+			assert_eq!(alphas[14..], [0; 2], "{}", line);
+		} else {
+			assert_eq!(*alphas, [0; SIZE], "{}", line);
+		}
+	}
+
+	// Round caps lie between the two.
+	let round = capped(LineCap::Round);
+	for ((butt, round), square) in butt
+		.iter()
+		.flatten()
+		.zip(round.iter().flatten())
+		.zip(square.iter().flatten())
+	{
+		assert!(butt <= round && round <= square);
+	}
+	assert!(round[7][2] > 0 && round[7][2] < 255, "{}", round[7][2]);
+	assert!(round[6][2] < round[7][2]);
+}
+
+/// A stroke 4 pixels wide turning left around (12, 12), so that its outer corner is at (14, 14).
+fn joined(join: LineJoin, miter_limit: f32) -> Vec<Vec<u8>> {
+	let stroke = Stroke::new(
+		vec![
+			Point::new(4., 12.),
+			Point::new(12., 12.),
+			Point::new(12., 4.),
+		],
+		&style(LineCap::Butt, join, miter_limit),
+		paint(),
+	);
+	alphas(&stroke, SIZE, SIZE)
+}
+
+#[test]
+fn joins() {
+	let miter = joined(LineJoin::Miter, 4.);
+	let round = joined(LineJoin::Round, 4.);
+	let bevel = joined(LineJoin::Bevel, 4.);
+
+	// The inner parts of the corner are covered by both segments.
+	for alphas in &[&miter, &round, &bevel] {
+		assert_eq!(alphas[12][10..13], [255; 3]);
+		assert_eq!(alphas[10][12..13], [255]);
+	}
+
+	// The outer corner pixel is filled by the miter, partly by the round join and not at all by the bevel.
+	assert_eq!(miter[13][13], 255);
+	assert!(
+		round[13][13] > 40 && round[13][13] < 128,
+		"{}",
+		round[13][13]
+	);
+	assert_eq!(bevel[13][13], 0);
+	// The bevel cuts diagonally through its neighbours.
+	assert!((120..=136).contains(&bevel[12][13]), "{}", bevel[12][13]);
+	assert_eq!(bevel[12][13], bevel[13][12]);
+
+	// Elsewhere, the joins agree.
+	for y in 0..SIZE {
+		for x in 0..SIZE {
+			if !(12..14).contains(&x) || !(12..14).contains(&y) {
+				assert_eq!(miter[y][x], bevel[y][x], "{} {}", x, y);
+				assert_eq!(round[y][x], bevel[y][x], "{} {}", x, y);
+			}
+		}
+	}
+}
+
+#[test]
+fn miter_limit() {
+	// A right angle's miter is √2 times half the width.
+	assert_eq!(joined(LineJoin::Miter, 1.5), joined(LineJoin::Miter, 4.));
+	assert_eq!(joined(LineJoin::Miter, 1.4), joined(LineJoin::Bevel, 4.));
+	assert_ne!(joined(LineJoin::Miter, 1.4), joined(LineJoin::Miter, 1.5));
+}
+
+fn hairline(points: &[(isize, isize)]) -> Vec<Vec<u8>> {
+	let stroke = Stroke::hairline(points.iter().map(|&(x, y)| Position { x, y }), paint());
+	alphas(&stroke, SIZE, SIZE)
+}
+
+/// Lists the covered pixels as `(x, y)`.
+fn pixels(alphas: &[Vec<u8>]) -> Vec<(usize, usize)> {
+	let mut pixels = Vec::new();
+	for (y, line) in alphas.iter().enumerate() {
+		for (x, alpha) in line.iter().enumerate() {
+			match alpha {
+				0 => (),
+				255 => pixels.push((x, y)),
+				_ => panic!("hairlines aren't anti-aliased: {} at {} {}", alpha, x, y),
+			}
+		}
+	}
+	pixels
+}
+
+#[test]
+fn hairlines() {
+	let x_major = [
+		(0, 0),
+		(1, 0),
+		(2, 1),
+		(3, 1),
+		(4, 2),
+		(5, 2),
+		(6, 3),
+		(7, 3),
+	];
+	assert_eq!(pixels(&hairline(&[(0, 0), (7, 3)])), x_major);
+	assert_eq!(pixels(&hairline(&[(7, 3), (0, 0)])), x_major);
+
+	let y_major = [(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (2, 5)];
+	assert_eq!(pixels(&hairline(&[(0, 0), (2, 5)])), y_major);
+	assert_eq!(pixels(&hairline(&[(2, 5), (0, 0)])), y_major);
+
+	// Mirrored slopes step the other way.
+	assert_eq!(
+		pixels(&hairline(&[(7, 0), (0, 3)])),
+		[
+			(6, 0),
+			(7, 0),
+			(4, 1),
+			(5, 1),
+			(2, 2),
+			(3, 2),
+			(0, 3),
+			(1, 3)
+		]
+	);
+
+	// Polylines share their vertices, and a single point is a single pixel.
+	assert_eq!(
+		pixels(&hairline(&[(1, 1), (4, 1), (4, 3)])),
+		[(1, 1), (2, 1), (3, 1), (4, 1), (4, 2), (4, 3)]
+	);
+	assert_eq!(pixels(&hairline(&[(5, 6)])), [(5, 6)]);
+
+	let stroke = Stroke::hairline(
+		vec![Position { x: 0, y: 0 }, Position { x: 7, y: 3 }],
+		paint(),
+	);
+	assert_eq!(stroke.lines(None), 0..4);
+	assert_eq!(stroke.line_segment(None, 1, 0..16), 2..4);
+	assert_eq!(stroke.line_segment(None, 4, 0..16), 0..0);
+}
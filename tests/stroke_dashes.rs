@@ -0,0 +1,187 @@
+//! Dashed strokes must follow their pattern, render the same in any segments and stay bounded for degenerate patterns.
+
+use scanline::{
+	drawables::{LineCap, Solid, Stroke, StrokeStyle},
+	geometry::{Path, Point},
+	pixel_formats::RgbaNoPadding,
+	render_segment, Position, Sprite,
+};
+use std::ops::Range;
+
+type Rgba8 = RgbaNoPadding<8>;
+
+/// Strokes a horizontal line through the middle of line 5 and returns which of the first 40 pixels in that line are covered.
+fn covered(dash_array: &[f32], dash_offset: f32, cap: LineCap) -> Vec<bool> {
+	let style = StrokeStyle {
+		width: 2.,
+		cap,
+		dash_array: dash_array.to_vec(),
+		dash_offset,
+		..StrokeStyle::default()
+	};
+	let stroke = Stroke::new(
+		vec![Point::new(0., 5.5), Point::new(40., 5.5)],
+		&style,
+		Solid::<Rgba8, _>::new([255, 255, 255, 255]),
+	);
+
+	let mut data = [0; 40 * 4];
+	let segment = Sprite::line_segment(&stroke, None, 5, 0..40);
+	let segment = segment.start.max(0)..segment.end.min(40);
+	if !segment.is_empty() {
+		#[allow(clippy::cast_sign_loss)]
+		let bytes = segment.start as usize * 4..segment.end as usize * 4;
+		Sprite::render(&stroke, None, 5, 0..40, segment, 0, &mut data[bytes]);
+	}
+	data.chunks_exact(4).map(|pixel| pixel[3] > 127).collect()
+}
+
+/// Lists the covered pixels as runs.
+fn runs(covered: &[bool]) -> Vec<(usize, usize)> {
+	let mut runs = Vec::new();
+	let mut start = None;
+	for (x, &covered) in covered.iter().chain(&[false]).enumerate() {
+		match (start, covered) {
+			(None, true) => start = Some(x),
+			(Some(run_start), false) => {
+				runs.push((run_start, x));
+				start = None;
+			}
+			_ => (),
+		}
+	}
+	runs
+}
+
+#[test]
+fn pattern() {
+	assert_eq!(
+		runs(&covered(&[10., 10.], 0., LineCap::Butt)),
+		[(0, 10), (20, 30)]
+	);
+	// Odd patterns are repeated once.
+	assert_eq!(
+		runs(&covered(&[10.], 0., LineCap::Butt)),
+		[(0, 10), (20, 30)]
+	);
+	assert_eq!(
+		runs(&covered(&[5., 10., 15.], 0., LineCap::Butt)),
+		[(0, 5), (15, 30), (35, 40)]
+	);
+}
+
+#[test]
+fn offset() {
+	assert_eq!(
+		runs(&covered(&[10., 10.], 5., LineCap::Butt)),
+		[(0, 5), (15, 25), (35, 40)]
+	);
+	// Offsets wrap around the pattern, in both directions.
+	assert_eq!(
+		runs(&covered(&[10., 10.], 45., LineCap::Butt)),
+		runs(&covered(&[10., 10.], 5., LineCap::Butt))
+	);
+	assert_eq!(
+		runs(&covered(&[10., 10.], -5., LineCap::Butt)),
+		[(5, 15), (25, 35)]
+	);
+}
+
+#[test]
+fn zero_length_dashes() {
+	// Dots centred at every multiple of 10, including both ends.
+	assert_eq!(
+		runs(&covered(&[0., 10.], 0., LineCap::Square)),
+		[(0, 1), (9, 11), (19, 21), (29, 31), (39, 40)]
+	);
+	// Without caps, they aren't visible.
+	assert_eq!(runs(&covered(&[0., 10.], 0., LineCap::Butt)), []);
+	// Zero-length gaps don't split dashes visibly.
+	assert_eq!(runs(&covered(&[10., 0.], 0., LineCap::Butt)), [(0, 40)]);
+}
+
+#[test]
+fn invalid_patterns_are_solid() {
+	let solid = covered(&[], 0., LineCap::Butt);
+	assert_eq!(runs(&solid), [(0, 40)]);
+	assert_eq!(covered(&[0., 0.], 0., LineCap::Butt), solid);
+	assert_eq!(covered(&[10., -1.], 0., LineCap::Butt), solid);
+	assert_eq!(covered(&[f32::NAN], 0., LineCap::Butt), solid);
+}
+
+#[test]
+fn dense_patterns_are_skipped() {
+	// These patterns would otherwise stop advancing or allocate without bound.
+	let nothing = vec![false; 40];
+	assert_eq!(covered(&[1e-10], 0., LineCap::Butt), nothing);
+	assert_eq!(covered(&[1e-10, 1e-10], 3., LineCap::Butt), nothing);
+	assert_eq!(covered(&[f32::MIN_POSITIVE], 0., LineCap::Round), nothing);
+	// The limit is 65536 dashes and gaps per polyline, so 40 pixels can be split into 32000 dashes but not 33000.
+	assert_eq!(
+		runs(&covered(&[40. / 32000., 0.], 0., LineCap::Butt)),
+		[(0, 40)]
+	);
+	assert_eq!(covered(&[40. / 33000., 0.], 0., LineCap::Butt), nothing);
+
+	// Only the polylines that would be split too finely are skipped.
+	let style = StrokeStyle {
+		width: 2.,
+		dash_array: vec![10.],
+		..StrokeStyle::default()
+	};
+	let path = Path::new()
+		.move_to(Point::new(0., 5.5))
+		.line_to(Point::new(40., 5.5))
+		.move_to(Point::new(0., 100.))
+		.line_to(Point::new(1e6, 100.));
+	let stroke = Stroke::path(&path, 0.25, &style, Solid::<Rgba8, _>::new([255; 4]));
+	assert_eq!(Sprite::lines(&stroke, None), 4..7);
+	assert_eq!(Sprite::line_segment(&stroke, None, 5, 0..40), 0..30);
+}
+
+/// Renders `segment_span` of `line` in a dashed diagonal stroke.
+fn render_dashed(line: isize, segment_span: Range<isize>) -> Vec<u8> {
+	let style = StrokeStyle {
+		width: 3.,
+		cap: LineCap::Round,
+		dash_array: vec![5., 3.],
+		dash_offset: 1.5,
+		..StrokeStyle::default()
+	};
+	let stroke = Stroke::new(
+		vec![Point::new(-2., 1.), Point::new(42., 11.)],
+		&style,
+		Solid::<Rgba8, _>::new([200, 100, 50, 255]),
+	);
+	let mut buffer = vec![0; segment_span.len() * 4];
+	render_segment(
+		&Some(0..12),
+		line,
+		0..40,
+		segment_span,
+		&mut buffer,
+		Some((Position { x: 0, y: 0 }, &stroke)),
+		None::<(Position, &Stroke<Solid<Rgba8, [u8; 4]>>)>,
+	);
+	buffer
+}
+
+#[test]
+fn segments_reassemble_to_lines() {
+	for line in 0..12 {
+		let whole = render_dashed(line, 0..40);
+		assert!(!(1..=10).contains(&line) || whole.iter().any(|byte| *byte != 0));
+		for splits in &[
+			&[0, 7, 13, 20, 31, 40][..],
+			&[0, 1, 2, 39, 40],
+			&[0, 20, 40],
+		] {
+			let pieces: Vec<u8> = splits
+				.iter()
+				.zip(splits.iter().skip(1))
+				.flat_map(|(start, end)| render_dashed(line, *start..*end))
+				.collect();
+			assert!(pieces == whole, "line {} split at {:?}", line, splits);
+		}
+	}
+}
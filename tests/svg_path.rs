@@ -0,0 +1,136 @@
+//! SVG path data must parse to the same paths as the equivalent builder calls.
+
+use scanline::geometry::{ParseSvgPathError, Path, Point};
+
+fn parse(data: &str) -> Path {
+	data.parse()
+		.unwrap_or_else(|error| panic!("{:?}: {}", data, error))
+}
+
+fn error(data: &str) -> ParseSvgPathError {
+	data.parse::<Path>().expect_err(data)
+}
+
+#[test]
+fn absolute_commands() {
+	assert_eq!(
+		parse("M10 20 L30 40 H50 V60 Z"),
+		Path::new()
+			.move_to(Point::new(10., 20.))
+			.line_to(Point::new(30., 40.))
+			.line_to(Point::new(50., 40.))
+			.line_to(Point::new(50., 60.))
+			.close()
+	);
+	assert_eq!(
+		parse("M0 0 C1 2 3 4 5 6 Q7 8 9 10"),
+		Path::new()
+			.move_to(Point::new(0., 0.))
+			.cubic_to(Point::new(1., 2.), Point::new(3., 4.), Point::new(5., 6.))
+			.quad_to(Point::new(7., 8.), Point::new(9., 10.))
+	);
+}
+
+#[test]
+fn relative_commands() {
+	assert_eq!(
+		parse("m10 20 l20 20 h20 v20 z"),
+		parse("M10 20 L30 40 H50 V60 Z")
+	);
+	assert_eq!(
+		parse("M1 1 c1 2 3 4 5 6 q1 1 2 2"),
+		parse("M1 1 C2 3 4 5 6 7 Q7 8 8 9")
+	);
+	// After closing, relative coordinates are based on the subpath's start.
+	assert_eq!(parse("M10 10 l5 0 z l1 1"), parse("M10 10 L15 10 Z L11 11"));
+}
+
+#[test]
+fn shorthand_curves_reflect_control_points() {
+	assert_eq!(
+		parse("M0 0 C0 1 2 1 2 0 S4 -1 4 0"),
+		parse("M0 0 C0 1 2 1 2 0 C2 -1 4 -1 4 0")
+	);
+	assert_eq!(
+		parse("M0 0 Q1 1 2 0 T4 0"),
+		parse("M0 0 Q1 1 2 0 Q3 -1 4 0")
+	);
+	// Without a preceding curve of the same kind, the current point is the control point.
+	assert_eq!(parse("M0 0 L1 1 T2 0"), parse("M0 0 L1 1 Q1 1 2 0"));
+	assert_eq!(
+		parse("M0 0 Q1 1 2 0 S3 1 4 0"),
+		parse("M0 0 Q1 1 2 0 C2 0 3 1 4 0")
+	);
+}
+
+#[test]
+fn implicit_repeats() {
+	assert_eq!(parse("M0 0 10 0 10 10"), parse("M0 0 L10 0 L10 10"));
+	assert_eq!(parse("m1 1 2 0 0 2"), parse("M1 1 L3 1 L3 3"));
+	assert_eq!(parse("M0 0 H1 2 V3 4"), parse("M0 0 H1 H2 V3 V4"));
+	assert_eq!(parse("M0 0 l1 1 1 1"), parse("M0 0 L1 1 L2 2"));
+}
+
+#[test]
+fn compact_numbers() {
+	assert_eq!(parse("M.5.5L-1-2"), parse("M 0.5 0.5 L -1 -2"));
+	assert_eq!(parse("M1e1,2E-1l+3,4"), parse("M10 0.2 L13 4.2"));
+	// An `e` without digits isn't an exponent.
+	assert_eq!(
+		error("M1e 2").to_string(),
+		"expected a number at byte 2 of SVG path data"
+	);
+}
+
+#[test]
+fn arcs() {
+	let built = Path::new().move_to(Point::new(0., 0.)).arc_to(
+		Point::new(10., 5.),
+		0.,
+		true,
+		false,
+		Point::new(10., 0.),
+	);
+	assert_eq!(parse("M0 0 A10 5 0 1 0 10 0"), built);
+	assert_eq!(parse("M0 0 a10 5 0 1 0 10 0"), built);
+	// Flags don't need separators.
+	assert_eq!(parse("M0 0 A10 5 0 1010 0"), built);
+	assert_eq!(parse("M0 0 A10,5,0,1,0,10,0"), built);
+	assert_eq!(
+		parse("M0 0 a10 5 0 0110 0 10 5 0 1 1 10 0"),
+		parse("M0 0 A10 5 0 0 1 10 0 A10 5 0 1 1 20 0")
+	);
+	// Arcs with a zero radius are straight lines.
+	assert_eq!(parse("M0 0 A0 5 0 1 0 10 0"), parse("M0 0 L10 0"));
+}
+
+#[test]
+fn errors() {
+	let cases: &[(&str, usize, &str)] = &[
+		("L1 1", 0, "a move-to command"),
+		("  1 1", 2, "a command"),
+		("M1", 2, "a number"),
+		("M1 1 L", 6, "a number"),
+		("M1 1 L2 x", 8, "a number"),
+		("M0 0 X1 1", 5, "a command"),
+		("M0 0 A1 1 0 2 0 1 1", 12, "a flag"),
+		("M0 0 A1 1 0 0 -1 1 1", 14, "a flag"),
+		("M0 0 Z 1 1", 7, "a command"),
+	];
+	for &(data, position, expected) in cases {
+		let error = error(data);
+		assert_eq!(error.position(), position, "{:?}", data);
+		assert_eq!(
+			error.to_string(),
+			format!(
+				"expected {} at byte {} of SVG path data",
+				expected, position
+			),
+			"{:?}",
+			data
+		);
+	}
+
+	assert_eq!(parse(""), Path::new());
+	assert_eq!(parse(" \n, "), Path::new());
+}
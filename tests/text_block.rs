@@ -0,0 +1,171 @@
+//! Text blocks must wrap greedily at spaces, or within words too wide to fit, and align their lines.
+
+mod common;
+
+use common::{
+	alphas,
+	font::{cmap_12_groups, font_with_glyphs, square},
+	Rgba8,
+};
+use scanline::{
+	drawables::{Alignment, Solid, TextBlock, TextStyle},
+	text::OutlineFont,
+};
+
+/// A font in which spaces advance 10 pixels at 16 pixels per em, and `a` is an 8 pixel square advancing 20.
+fn font_data() -> Vec<u8> {
+	font_with_glyphs(
+		cmap_12_groups(2, &[[0x20, 0x20, 1], [0x61, 0x61, 2]]),
+		&[Vec::new(), Vec::new(), square(8)],
+	)
+}
+
+fn block(
+	font: &OutlineFont<'_>,
+	max_width: f32,
+	alignment: Alignment,
+	text: &str,
+) -> TextBlock<Solid<Rgba8, [u8; 4]>> {
+	let style = TextStyle {
+		max_width,
+		alignment,
+		..TextStyle::default()
+	};
+	TextBlock::new(font, style, text, Solid::new([255; 4]))
+}
+
+/// Lists the covered pixel runs of each text line, which are 16 pixels apart with squares on lines 4 to 12 of each.
+fn runs(block: &TextBlock<Solid<Rgba8, [u8; 4]>>) -> Vec<Vec<(usize, usize)>> {
+	let alphas = alphas(block, 120, 64);
+	(0..block.line_count())
+		.map(|line| {
+			let mut runs = Vec::new();
+			let mut start = None;
+			for (x, alpha) in alphas[line * 16 + 8].iter().chain(&[0]).enumerate() {
+				match (start, *alpha > 127) {
+					(None, true) => start = Some(x),
+					(Some(run_start), false) => {
+						runs.push((run_start, x));
+						start = None;
+					}
+					_ => (),
+				}
+			}
+			runs
+		})
+		.collect()
+}
+
+#[test]
+fn greedy_wrapping() {
+	let data = font_data();
+	let font = OutlineFont::new(&data).unwrap();
+
+	// 40 + 10 + 40 fits into 100 pixels, but another word doesn't.
+	let wrapped = block(&font, 100., Alignment::Left, "aa aa  aa");
+	assert_eq!(wrapped.line_count(), 2);
+	assert_eq!(
+		runs(&wrapped),
+		[
+			vec![(0, 8), (20, 28), (50, 58), (70, 78)],
+			vec![(0, 8), (20, 28)]
+		]
+	);
+	assert_eq!(wrapped.width(), 100.);
+	assert_eq!(wrapped.height(), 32.);
+
+	// Lines may be exactly as wide as the block.
+	assert_eq!(block(&font, 90., Alignment::Left, "aa aa").line_count(), 1);
+	assert_eq!(block(&font, 89., Alignment::Left, "aa aa").line_count(), 2);
+
+	// Paragraphs always start a new line, and without a maximum the widest line sets the width.
+	let paragraphs = block(&font, f32::INFINITY, Alignment::Left, "aa aa aa\naa\n\naaa");
+	assert_eq!(paragraphs.line_count(), 4);
+	assert_eq!(paragraphs.width(), 140.);
+	assert_eq!(paragraphs.height(), 64.);
+	assert_eq!(runs(&paragraphs)[2], []);
+}
+
+#[test]
+fn line_spacing() {
+	let data = font_data();
+	let font = OutlineFont::new(&data).unwrap();
+	let style = TextStyle {
+		line_spacing: 1.5,
+		..TextStyle::default()
+	};
+	let block = TextBlock::new(&font, style, "a\na\na", Solid::<Rgba8, _>::new([255; 4]));
+	assert_eq!(block.line_count(), 3);
+	assert_eq!(block.height(), 72.);
+
+	// The squares' baselines are at 12, 36 and 60.
+	let alphas = alphas(&block, 8, 64);
+	let covered: Vec<usize> = (0..64).filter(|line| alphas[*line][0] > 127).collect();
+	let expected: Vec<usize> = (4..12).chain(28..36).chain(52..60).collect();
+	assert_eq!(covered, expected);
+}
+
+#[test]
+fn alignment() {
+	let data = font_data();
+	let font = OutlineFont::new(&data).unwrap();
+	let text = "aa aa aa";
+
+	assert_eq!(
+		runs(&block(&font, 100., Alignment::Right, text))[1],
+		[(60, 68), (80, 88)]
+	);
+	assert_eq!(
+		runs(&block(&font, 100., Alignment::Center, text))[1],
+		[(30, 38), (50, 58)]
+	);
+
+	// Justified lines stretch their spaces to touch both edges, except at the end of a paragraph.
+	assert_eq!(
+		runs(&block(&font, 100., Alignment::Justify, text)),
+		[
+			vec![(0, 8), (20, 28), (60, 68), (80, 88)],
+			vec![(0, 8), (20, 28)]
+		]
+	);
+	assert_eq!(
+		runs(&block(&font, 100., Alignment::Justify, "aa aa\naa aa aa")),
+		[
+			vec![(0, 8), (20, 28), (50, 58), (70, 78)],
+			vec![(0, 8), (20, 28), (60, 68), (80, 88)],
+			vec![(0, 8), (20, 28)]
+		]
+	);
+}
+
+#[test]
+fn overlong_words() {
+	let data = font_data();
+	let font = OutlineFont::new(&data).unwrap();
+
+	// Words are broken at the last character that fits.
+	let broken = block(&font, 50., Alignment::Left, "aaaaa");
+	assert_eq!(broken.line_count(), 3);
+	assert_eq!(
+		runs(&broken),
+		[vec![(0, 8), (20, 28)], vec![(0, 8), (20, 28)], vec![(0, 8)]]
+	);
+
+	// They start on a line of their own, and their remainder is wrapped like a word.
+	let after_word = block(&font, 50., Alignment::Left, "a aaaa a");
+	assert_eq!(after_word.line_count(), 4);
+	assert_eq!(
+		runs(&after_word),
+		[
+			vec![(0, 8)],
+			vec![(0, 8), (20, 28)],
+			vec![(0, 8), (20, 28)],
+			vec![(0, 8)]
+		]
+	);
+
+	// A single character wider than the block still takes a line.
+	let narrow = block(&font, 5., Alignment::Left, "aa");
+	assert_eq!(narrow.line_count(), 2);
+	assert_eq!(runs(&narrow), [vec![(0, 8)], vec![(0, 8)]]);
+}